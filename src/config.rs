@@ -0,0 +1,66 @@
+use crate::diagnostics::Severity;
+use crate::gsn::lint::LintOptions;
+use crate::gsn::metrics::Threshold;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+///
+/// Configuration file
+///
+/// ```yaml
+/// severity:
+///   C05: allow
+///   V02: deny
+/// lint:
+///   maxWords: 30
+///   placeholders: [TODO, TBD]
+/// thresholds:
+///   undevelopedGoals: { max: 5 }
+/// ```
+///
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    #[serde(default)]
+    pub(crate) severity: BTreeMap<String, Severity>,
+    #[serde(default)]
+    pub(crate) lint: LintOptions,
+    #[serde(default)]
+    pub(crate) thresholds: BTreeMap<String, Threshold>,
+}
+
+///
+/// Read the configuration from `path`.
+///
+pub fn read_config(path: &str) -> Result<Config> {
+    let contents = std::fs::read_to_string(path)
+        .context(format!("Failed to open configuration file {}", path))?;
+    serde_yaml::from_str(&contents).context(format!("Failed to parse configuration file {}", path))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_severities() {
+        let config: Config =
+            serde_yaml::from_str("severity:\n  C05: allow\n  V02: deny\n").unwrap();
+        assert_eq!(config.severity.get("C05"), Some(&Severity::Allow));
+        assert_eq!(config.severity.get("V02"), Some(&Severity::Deny));
+        assert!(serde_yaml::from_str::<Config>("severity:\n  C05: ignore\n").is_err());
+        assert!(serde_yaml::from_str::<Config>("unknown: 1\n").is_err());
+        assert_eq!(config.lint.max_words, 50);
+        assert!(config.thresholds.is_empty());
+        let config: Config =
+            serde_yaml::from_str("thresholds:\n  depth: { min: 2, max: 10 }\n").unwrap();
+        assert_eq!(
+            config.thresholds.get("depth"),
+            Some(&Threshold {
+                min: Some(2.0),
+                max: Some(10.0)
+            })
+        );
+    }
+}
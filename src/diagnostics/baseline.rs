@@ -0,0 +1,159 @@
+use super::{DiagType, Diagnostics, MessageIdentity};
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fs::File;
+
+///
+/// Read the identities of known warnings from the baseline file at `path`.
+///
+pub fn read_baseline(path: &str) -> Result<Vec<MessageIdentity>> {
+    let contents =
+        std::fs::read_to_string(path).context(format!("Failed to open baseline file {}", path))?;
+    serde_yaml::from_str(&contents).context(format!("Failed to parse baseline file {}", path))
+}
+
+///
+/// Write the identities of all current warnings to the baseline file at `path`.
+///
+pub fn write_baseline(path: &str, diags: &Diagnostics) -> Result<()> {
+    let mut identities: Vec<MessageIdentity> = diags
+        .messages
+        .iter()
+        .filter(|m| m.diag_type == DiagType::Warning)
+        .map(|m| m.identity())
+        .collect();
+    identities.sort();
+    let file = File::create(path).context(format!("Failed to open baseline file {}", path))?;
+    serde_yaml::to_writer(file, &identities)?;
+    Ok(())
+}
+
+impl Diagnostics {
+    ///
+    /// Remove all warnings that are already known in the `baseline`.
+    ///
+    /// Each entry of the baseline matches at most one warning,
+    /// thus additional warnings with the same identity are still reported.
+    /// Errors are never removed.
+    ///
+    pub fn apply_baseline(&mut self, baseline: &[MessageIdentity]) {
+        let mut known = BTreeMap::<&MessageIdentity, usize>::new();
+        for identity in baseline {
+            *known.entry(identity).or_insert(0) += 1;
+        }
+        let warnings = &mut self.warnings;
+        self.messages.retain(|m| {
+            if m.diag_type != DiagType::Warning {
+                return true;
+            }
+            match known.get_mut(&m.identity()) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    *warnings -= 1;
+                    false
+                }
+                _ => true,
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn diags() -> Diagnostics {
+        let mut d = Diagnostics::default();
+        d.add_reference_warning_at(
+            Some("main"),
+            Some("G1"),
+            "G2",
+            None,
+            "V05: Element G1 has duplicate entry G2 in supported by.".to_owned(),
+        );
+        d.add_reference_warning_at(
+            Some("main"),
+            Some("G1"),
+            "G3",
+            None,
+            "V05: Element G1 has duplicate entry G3 in supported by.".to_owned(),
+        );
+        d.add_keyed_warning(None, "x", "C05: Level x is only used once.".to_owned());
+        d.add_reference_error_at(
+            Some("main"),
+            Some("G1"),
+            "G4",
+            None,
+            "C03: Element G1 has unresolved supported by element: G4".to_owned(),
+        );
+        d
+    }
+
+    #[test]
+    fn identities() {
+        let d = diags();
+        let identity = d.messages[0].identity();
+        assert_ne!(identity, d.messages[1].identity());
+        assert_eq!(identity.element.as_deref(), Some("G1"));
+        assert_eq!(identity.reference.as_deref(), Some("G2"));
+        assert_eq!(identity.key, None);
+        assert_eq!(d.messages[2].identity().key.as_deref(), Some("x"));
+    }
+
+    #[test]
+    fn filter_known_warnings() {
+        let mut d = diags();
+        let baseline = vec![d.messages[0].identity(), d.messages[3].identity()];
+        d.apply_baseline(&baseline);
+        assert_eq!(d.messages.len(), 3);
+        assert_eq!(d.warnings, 2);
+        assert_eq!(d.errors, 1);
+        assert!(d.messages[0].msg.contains("G3"));
+    }
+
+    #[test]
+    fn element_less_warning_survives_edits() {
+        use crate::gsn::{check::check_nodes, GsnNode};
+        use crate::yaml_fix::MyMap;
+        let goal = |supported_by: &[&str]| GsnNode {
+            supported_by: (!supported_by.is_empty())
+                .then(|| supported_by.iter().map(|s| s.to_string()).collect()),
+            undeveloped: supported_by.is_empty().then_some(true),
+            ..Default::default()
+        };
+        let mut nodes = MyMap::<String, GsnNode>::new();
+        nodes.insert("G1".to_owned(), goal(&[]));
+        nodes.insert("G2".to_owned(), goal(&[]));
+        let mut d = Diagnostics::default();
+        check_nodes(&mut d, &nodes, None);
+        let baseline: Vec<MessageIdentity> = d.messages.iter().map(|m| m.identity()).collect();
+        assert_eq!(baseline.len(), 1);
+        // G1 is developed further and G2 is renamed, thus the text of C01 changes.
+        nodes.insert("G1".to_owned(), goal(&["G3"]));
+        nodes.insert("G3".to_owned(), goal(&[]));
+        let g2 = nodes.remove("G2").unwrap();
+        nodes.insert("G4".to_owned(), g2);
+        let mut d = Diagnostics::default();
+        check_nodes(&mut d, &nodes, None);
+        assert_eq!(
+            d.messages[0].msg,
+            "C01: There is more than one unreferenced element: G1, G4."
+        );
+        d.apply_baseline(&baseline);
+        assert!(d.messages.is_empty());
+        assert_eq!(d.warnings, 0);
+    }
+
+    #[test]
+    fn write_and_read() {
+        let file = assert_fs::NamedTempFile::new("baseline.yaml").unwrap();
+        let path = file.path().to_str().unwrap();
+        let mut d = diags();
+        write_baseline(path, &d).unwrap();
+        let baseline = read_baseline(path).unwrap();
+        assert_eq!(baseline.len(), 3);
+        d.apply_baseline(&baseline);
+        assert_eq!(d.warnings, 0);
+        assert_eq!(d.messages.len(), 1);
+    }
+}
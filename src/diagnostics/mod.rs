@@ -0,0 +1,451 @@
+use crate::gsn::get_display_id;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+pub mod baseline;
+pub mod render;
+mod report;
+
+#[derive(Debug, PartialEq)]
+pub enum DiagType {
+    Warning,
+    Error,
+}
+
+///
+/// Configured severity of a diagnostic code
+///
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Warnings are not reported at all
+    Allow,
+    /// Report as warning
+    Warn,
+    /// Warnings are reported as errors
+    Deny,
+}
+
+///
+/// Location in an input file, lines and columns start at 1.
+/// The length is the number of characters of the located text.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SourceLocation {
+    pub file: String,
+    pub line: usize,
+    pub col: usize,
+    pub len: usize,
+}
+
+impl Display for SourceLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.col)
+    }
+}
+
+pub struct DiagMsg {
+    pub diag_type: DiagType,
+    pub module: Option<String>,
+    /// The element the message is about
+    pub element: Option<String>,
+    /// The element referenced by `element` the message is about
+    pub reference: Option<String>,
+    /// What the message is about, if it is not about an element e.g., the level of C05
+    pub key: Option<String>,
+    pub location: Option<SourceLocation>,
+    pub msg: String,
+    /// Secondary locations with a label e.g., where an element was first defined
+    pub labels: Vec<(SourceLocation, String)>,
+}
+
+///
+/// Identity of a message that is stable across edits of the input files
+///
+/// Messages about an element are identified by their code, module and element,
+/// messages about a reference additionally by the referenced element.
+/// Messages without an element are identified by their key, if any.
+///
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct MessageIdentity {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) module: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) element: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) reference: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) key: Option<String>,
+}
+
+impl DiagMsg {
+    fn new(
+        diag_type: DiagType,
+        module: Option<&str>,
+        element: Option<&str>,
+        location: Option<&SourceLocation>,
+        msg: String,
+    ) -> Self {
+        DiagMsg {
+            diag_type,
+            module: module.map(|m| m.to_owned()),
+            element: element.map(|e| e.to_owned()),
+            reference: None,
+            key: None,
+            location: location.cloned(),
+            msg,
+            labels: Vec::new(),
+        }
+    }
+
+    ///
+    /// Get the identity of the message.
+    ///
+    pub fn identity(&self) -> MessageIdentity {
+        MessageIdentity {
+            code: self.split_code().0.map(|c| c.to_owned()),
+            module: self.module.to_owned(),
+            element: self.get_element().map(|e| e.to_owned()),
+            reference: self
+                .reference
+                .as_deref()
+                .map(|r| get_display_id(r, self.module.as_deref().unwrap_or_default()).to_owned()),
+            key: self.key.to_owned(),
+        }
+    }
+
+    ///
+    /// Get the element the message is about.
+    /// The module name is omitted for elements of the module of the message.
+    ///
+    pub fn get_element(&self) -> Option<&str> {
+        self.element
+            .as_deref()
+            .map(|e| get_display_id(e, self.module.as_deref().unwrap_or_default()))
+    }
+
+    ///
+    /// Get the diagnostic code e.g., "C03" and the message text without it.
+    ///
+    pub fn split_code(&self) -> (Option<&str>, &str) {
+        match self.msg.split_once(": ") {
+            Some((code, text))
+                if code.len() == 3
+                    && code.starts_with(|c: char| c.is_ascii_uppercase())
+                    && code[1..].chars().all(|c| c.is_ascii_digit()) =>
+            {
+                (Some(code), text)
+            }
+            _ => (None, &self.msg),
+        }
+    }
+}
+
+impl Display for DiagMsg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.diag_type {
+            DiagType::Error => write!(f, "Error:")?,
+            DiagType::Warning => write!(f, "Warning:")?,
+        }
+        if let Some(module) = &self.module {
+            write!(f, " ({})", module)?;
+        }
+        if let Some(location) = &self.location {
+            write!(f, " {}:", location)?;
+        }
+        write!(f, " {}", self.msg)
+    }
+}
+
+///
+/// Errors about unknown elements or types can not be suppressed,
+/// since the argument can not be rendered with them.
+///
+const UNSUPPRESSIBLE: [&str; 2] = ["V01", "C03"];
+
+#[derive(Default)]
+pub struct Diagnostics {
+    pub messages: Vec<DiagMsg>,
+    pub warnings: usize,
+    pub errors: usize,
+    /// Severities that differ from the default of a diagnostic code
+    pub severities: BTreeMap<String, Severity>,
+    /// Diagnostic codes that are suppressed per element
+    pub suppressions: BTreeMap<String, Vec<String>>,
+}
+
+impl Diagnostics {
+    pub fn add_error(&mut self, module: Option<&str>, msg: String) {
+        self.add_msg(DiagType::Error, module, msg);
+    }
+
+    pub fn add_warning(&mut self, module: Option<&str>, msg: String) {
+        self.add_msg(DiagType::Warning, module, msg);
+    }
+
+    ///
+    /// Add an error that refers to a location in an input file.
+    ///
+    pub fn add_error_at(
+        &mut self,
+        module: Option<&str>,
+        element: Option<&str>,
+        location: Option<&SourceLocation>,
+        msg: String,
+    ) {
+        self.add_located_msg(DiagType::Error, module, element, location, Vec::new(), msg);
+    }
+
+    ///
+    /// Add a warning that refers to a location in an input file.
+    ///
+    pub fn add_warning_at(
+        &mut self,
+        module: Option<&str>,
+        element: Option<&str>,
+        location: Option<&SourceLocation>,
+        msg: String,
+    ) {
+        self.add_located_msg(
+            DiagType::Warning,
+            module,
+            element,
+            location,
+            Vec::new(),
+            msg,
+        );
+    }
+
+    ///
+    /// Add an error about the reference from `element` to `reference`.
+    ///
+    pub fn add_reference_error_at(
+        &mut self,
+        module: Option<&str>,
+        element: Option<&str>,
+        reference: &str,
+        location: Option<&SourceLocation>,
+        msg: String,
+    ) {
+        self.add_diag_msg(DiagMsg {
+            reference: Some(reference.to_owned()),
+            ..DiagMsg::new(DiagType::Error, module, element, location, msg)
+        });
+    }
+
+    ///
+    /// Add a warning about the reference from `element` to `reference`.
+    ///
+    pub fn add_reference_warning_at(
+        &mut self,
+        module: Option<&str>,
+        element: Option<&str>,
+        reference: &str,
+        location: Option<&SourceLocation>,
+        msg: String,
+    ) {
+        self.add_diag_msg(DiagMsg {
+            reference: Some(reference.to_owned()),
+            ..DiagMsg::new(DiagType::Warning, module, element, location, msg)
+        });
+    }
+
+    ///
+    /// Add a warning that is not about an element, but about `key` e.g., a level.
+    /// The key identifies the warning in a baseline.
+    ///
+    pub fn add_keyed_warning(&mut self, module: Option<&str>, key: &str, msg: String) {
+        self.add_diag_msg(DiagMsg {
+            key: Some(key.to_owned()),
+            ..DiagMsg::new(DiagType::Warning, module, None, None, msg)
+        });
+    }
+
+    pub fn add_msg(&mut self, dtype: DiagType, module: Option<&str>, msg: String) {
+        self.add_located_msg(dtype, module, None, None, Vec::new(), msg);
+    }
+
+    ///
+    /// Add a message with an optional location in an input file.
+    /// `labels` are secondary locations e.g., where an element was first defined.
+    ///
+    /// The severity of warnings is adjusted as configured in `severities`.
+    /// Errors always stay errors.
+    ///
+    pub fn add_located_msg(
+        &mut self,
+        dtype: DiagType,
+        module: Option<&str>,
+        element: Option<&str>,
+        location: Option<&SourceLocation>,
+        labels: Vec<(SourceLocation, String)>,
+        msg: String,
+    ) {
+        self.add_diag_msg(DiagMsg {
+            labels,
+            ..DiagMsg::new(dtype, module, element, location, msg)
+        });
+    }
+
+    ///
+    /// Add a message, the severity of warnings is adjusted as configured in `severities`.
+    /// Messages about an element that suppresses their code are dropped.
+    ///
+    fn add_diag_msg(&mut self, mut d: DiagMsg) {
+        if let (Some(code), Some(element)) = (d.split_code().0, &d.element) {
+            if !UNSUPPRESSIBLE.contains(&code)
+                && self
+                    .suppressions
+                    .get(element)
+                    .is_some_and(|s| s.iter().any(|c| c == code))
+            {
+                return;
+            }
+        }
+        if d.diag_type == DiagType::Warning {
+            match d.split_code().0.and_then(|c| self.severities.get(c)) {
+                Some(Severity::Allow) => return,
+                Some(Severity::Deny) => d.diag_type = DiagType::Error,
+                Some(Severity::Warn) | None => (),
+            }
+        }
+        match d.diag_type {
+            DiagType::Error => self.errors += 1,
+            DiagType::Warning => self.warnings += 1,
+        }
+        self.messages.push(d);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn debug() {
+        assert_eq!(format!("{:?}", DiagType::Warning), "Warning");
+    }
+
+    #[test]
+    fn add_and_print() {
+        let mut d = Diagnostics::default();
+        d.add_warning(Some("module"), "msg".to_owned());
+        d.add_error(Some("module"), "errmsg".to_owned());
+        assert_eq!(
+            format!("{}", d.messages.get(0).unwrap()),
+            "Warning: (module) msg".to_owned()
+        );
+        assert_eq!(
+            format!("{}", d.messages.get(1).unwrap()),
+            "Error: (module) errmsg".to_owned()
+        );
+    }
+
+    #[test]
+    fn suppressions() {
+        let mut d = Diagnostics::default();
+        d.suppressions
+            .insert("G1".to_owned(), vec!["C04".to_owned(), "C03".to_owned()]);
+        d.add_error_at(
+            None,
+            Some("G1"),
+            None,
+            "C04: Cycle detected at element G1. Cycle is G1 -> G1.".to_owned(),
+        );
+        d.add_error_at(
+            None,
+            Some("G2"),
+            None,
+            "C04: Cycle detected at element G2. Cycle is G2 -> G2.".to_owned(),
+        );
+        d.add_error_at(
+            None,
+            Some("G1"),
+            None,
+            "C03: Element G1 has unresolved context: C1".to_owned(),
+        );
+        // Unknown elements can not be suppressed.
+        assert_eq!(d.messages.len(), 2);
+        assert_eq!(d.messages[0].get_element(), Some("G2"));
+        assert_eq!(d.messages[1].split_code().0, Some("C03"));
+        assert_eq!(d.errors, 2);
+    }
+
+    #[test]
+    fn configured_severities() {
+        let mut d = Diagnostics::default();
+        d.severities.insert("V02".to_owned(), Severity::Allow);
+        d.severities.insert("C05".to_owned(), Severity::Deny);
+        d.severities.insert("V01".to_owned(), Severity::Allow);
+        d.add_warning(None, "V02: Element G1 is undeveloped.".to_owned());
+        d.add_warning(None, "C05: Level x is only used once.".to_owned());
+        d.add_error(None, "V01: Element G1 is of unknown type.".to_owned());
+        d.add_warning(None, "C01: More than one unreferenced element.".to_owned());
+        assert_eq!(d.messages.len(), 3);
+        assert_eq!(d.errors, 2);
+        assert_eq!(d.warnings, 1);
+        assert_eq!(d.messages[0].diag_type, DiagType::Error);
+        assert_eq!(d.messages[1].diag_type, DiagType::Error);
+        assert_eq!(d.messages[2].diag_type, DiagType::Warning);
+    }
+
+    #[test]
+    fn split_codes() {
+        let mut d = Diagnostics::default();
+        d.add_error(None, "C03: Unknown".to_owned());
+        d.add_error(None, "Failed: reason".to_owned());
+        d.add_error(None, "no code".to_owned());
+        assert_eq!(d.messages[0].split_code(), (Some("C03"), "Unknown"));
+        assert_eq!(d.messages[1].split_code(), (None, "Failed: reason"));
+        assert_eq!(d.messages[2].split_code(), (None, "no code"));
+    }
+
+    #[test]
+    fn add_and_print_location() {
+        let mut d = Diagnostics::default();
+        let location = SourceLocation {
+            file: "main.gsn.yaml".to_owned(),
+            line: 3,
+            col: 5,
+            len: 2,
+        };
+        d.add_error_at(Some("module"), None, Some(&location), "errmsg".to_owned());
+        d.add_warning_at(None, None, Some(&location), "msg".to_owned());
+        assert_eq!(
+            format!("{}", d.messages[0]),
+            "Error: (module) main.gsn.yaml:3:5: errmsg".to_owned()
+        );
+        assert_eq!(
+            format!("{}", d.messages[1]),
+            "Warning: main.gsn.yaml:3:5: msg".to_owned()
+        );
+        assert_eq!(d.errors, 1);
+        assert_eq!(d.warnings, 1);
+    }
+
+    #[test]
+    fn labels_of_allowed_warnings() {
+        let mut d = Diagnostics::default();
+        d.severities.insert("V05".to_owned(), Severity::Allow);
+        let location = SourceLocation {
+            file: "main.gsn.yaml".to_owned(),
+            line: 3,
+            col: 5,
+            len: 2,
+        };
+        d.add_error_at(None, None, Some(&location), "C03: Unknown".to_owned());
+        d.add_located_msg(
+            DiagType::Warning,
+            None,
+            None,
+            Some(&location),
+            vec![(location.to_owned(), "here".to_owned())],
+            "V05: Duplicate".to_owned(),
+        );
+        assert_eq!(d.messages.len(), 1);
+        assert!(d.messages[0].labels.is_empty());
+    }
+}
@@ -0,0 +1,248 @@
+use super::report::{render_github, render_json, render_junit, render_sarif};
+use super::{DiagMsg, DiagType, Diagnostics, SourceLocation};
+use std::collections::BTreeMap;
+use std::io::Write;
+
+///
+/// How diagnostic messages are printed
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MessageFormat {
+    /// One line per message, suitable for CI logs and grep
+    Plain,
+    /// Source snippets with carets under the offending text, like rustc
+    Rich,
+    /// JSON array of all messages
+    Json,
+    /// SARIF 2.1.0 log for code scanning tools
+    Sarif,
+    /// JUnit XML for test reporters
+    Junit,
+    /// GitHub workflow commands that show up as annotations
+    Github,
+}
+
+impl MessageFormat {
+    ///
+    /// Machine-readable formats are meant for other tools, not for humans.
+    ///
+    pub fn is_machine_readable(&self) -> bool {
+        !matches!(self, MessageFormat::Plain | MessageFormat::Rich)
+    }
+}
+
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+///
+/// Render all messages of `diags` to `output`.
+///
+/// Colors are only used for the rich format and only if `color` is set.
+///
+pub fn render_messages(
+    output: &mut impl Write,
+    diags: &Diagnostics,
+    format: MessageFormat,
+    color: bool,
+) -> std::io::Result<()> {
+    let mut renderer = RichRenderer {
+        sources: BTreeMap::new(),
+        color,
+    };
+    match format {
+        MessageFormat::Plain => {
+            for msg in &diags.messages {
+                writeln!(output, "{}", msg)?;
+            }
+        }
+        MessageFormat::Rich => {
+            for msg in &diags.messages {
+                renderer.render(output, msg)?;
+            }
+        }
+        MessageFormat::Json => render_json(output, diags)?,
+        MessageFormat::Sarif => render_sarif(output, diags)?,
+        MessageFormat::Junit => render_junit(output, diags)?,
+        MessageFormat::Github => render_github(output, diags)?,
+    }
+    Ok(())
+}
+
+struct RichRenderer {
+    /// Input files read so far, `None` if the file could not be read.
+    sources: BTreeMap<String, Option<Vec<String>>>,
+    color: bool,
+}
+
+impl RichRenderer {
+    fn paint(&self, text: &str, style: &str) -> String {
+        if self.color {
+            format!("{}{}{}", style, text, RESET)
+        } else {
+            text.to_owned()
+        }
+    }
+
+    fn source_line(&mut self, location: &SourceLocation) -> Option<String> {
+        self.sources
+            .entry(location.file.to_owned())
+            .or_insert_with(|| {
+                std::fs::read_to_string(&location.file)
+                    .ok()
+                    .map(|s| s.lines().map(|l| l.to_owned()).collect())
+            })
+            .as_ref()
+            .and_then(|lines| lines.get(location.line.wrapping_sub(1)).cloned())
+    }
+
+    fn render(&mut self, output: &mut impl Write, msg: &DiagMsg) -> std::io::Result<()> {
+        let (code, text) = msg.split_code();
+        let (level, style) = match msg.diag_type {
+            DiagType::Error => ("error", RED),
+            DiagType::Warning => ("warning", YELLOW),
+        };
+        let header = match code {
+            Some(code) => format!("{}[{}]", level, code),
+            None => level.to_owned(),
+        };
+        writeln!(
+            output,
+            "{}{}",
+            self.paint(&header, style),
+            self.paint(&format!(": {}", text), BOLD)
+        )?;
+        let mut snippets = Vec::new();
+        if let Some(location) = &msg.location {
+            snippets.push((location, '^', "", style));
+        }
+        for (location, label) in &msg.labels {
+            snippets.push((location, '-', label.as_str(), BLUE));
+        }
+        let width = snippets
+            .iter()
+            .map(|(l, ..)| l.line.to_string().len())
+            .max()
+            .unwrap_or(0);
+        let gutter = " ".repeat(width);
+        if let Some(location) = &msg.location {
+            writeln!(output, "{}{} {}", gutter, self.paint("-->", BLUE), location)?;
+        }
+        for (location, marker, label, style) in snippets {
+            if msg.location.as_ref() != Some(location) {
+                writeln!(output, "{}{} {}", gutter, self.paint("::", BLUE), location)?;
+            }
+            if let Some(line) = self.source_line(location) {
+                // Keep tabs so that the markers line up with the source line.
+                let indent: String = line
+                    .chars()
+                    .take(location.col.saturating_sub(1))
+                    .map(|c| if c == '\t' { '\t' } else { ' ' })
+                    .collect();
+                let markers = marker.to_string().repeat(location.len.max(1));
+                writeln!(output, "{} {}", gutter, self.paint("|", BLUE))?;
+                writeln!(
+                    output,
+                    "{} {} {}",
+                    self.paint(&format!("{:>width$}", location.line, width = width), BLUE),
+                    self.paint("|", BLUE),
+                    line
+                )?;
+                let markers = if label.is_empty() {
+                    markers
+                } else {
+                    format!("{} {}", markers, label)
+                };
+                writeln!(
+                    output,
+                    "{} {} {}{}",
+                    gutter,
+                    self.paint("|", BLUE),
+                    indent,
+                    self.paint(&markers, style)
+                )?;
+            }
+        }
+        if let Some(module) = &msg.module {
+            writeln!(
+                output,
+                "{} {} module: {}",
+                gutter,
+                self.paint("=", BLUE),
+                module
+            )?;
+        }
+        writeln!(output)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn render(diags: &Diagnostics, format: MessageFormat) -> String {
+        let mut output = Vec::new();
+        render_messages(&mut output, diags, format, false).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn plain_format() {
+        let mut d = Diagnostics::default();
+        d.add_error(Some("mod"), "C01: Failure".to_owned());
+        assert_eq!(
+            render(&d, MessageFormat::Plain),
+            "Error: (mod) C01: Failure\n"
+        );
+    }
+
+    #[test]
+    fn rich_format_without_source() {
+        let mut d = Diagnostics::default();
+        d.add_warning(None, "C05: Something".to_owned());
+        assert_eq!(
+            render(&d, MessageFormat::Rich),
+            "warning[C05]: Something\n\n"
+        );
+    }
+
+    #[test]
+    fn rich_format_with_snippets() {
+        let file = "tests/locations.gsn.test.yaml";
+        let source = std::fs::read_to_string(file).unwrap();
+        let line = source.lines().nth(2).unwrap();
+        let col = line.find("G3").unwrap() + 1;
+        let mut d = Diagnostics::default();
+        let location = SourceLocation {
+            file: file.to_owned(),
+            line: 3,
+            col,
+            len: 2,
+        };
+        d.add_located_msg(
+            DiagType::Error,
+            Some("mod"),
+            Some("G1"),
+            Some(&location),
+            vec![(
+                SourceLocation {
+                    file: file.to_owned(),
+                    line: 1,
+                    col: 1,
+                    len: 2,
+                },
+                "defined here".to_owned(),
+            )],
+            "C03: Unknown".to_owned(),
+        );
+        let output = render(&d, MessageFormat::Rich);
+        let expected = format!(
+            "error[C03]: Unknown\n --> {file}:3:{col}\n  |\n3 | {line}\n  | {}^^\n :: {file}:1:1\n  |\n1 | {}\n  | -- defined here\n  = module: mod\n\n",
+            " ".repeat(col - 1),
+            source.lines().next().unwrap(),
+        );
+        assert_eq!(output, expected);
+    }
+}
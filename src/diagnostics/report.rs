@@ -0,0 +1,348 @@
+use super::{DiagMsg, DiagType, Diagnostics, SourceLocation};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::io::Write;
+
+///
+/// Name of the severity as used in the reports.
+///
+fn severity(msg: &DiagMsg) -> &'static str {
+    match msg.diag_type {
+        DiagType::Error => "error",
+        DiagType::Warning => "warning",
+    }
+}
+
+///
+/// Render all messages as a JSON array.
+///
+pub fn render_json(output: &mut impl Write, diags: &Diagnostics) -> std::io::Result<()> {
+    let location = |l: &SourceLocation| {
+        json!({
+            "file": l.file,
+            "line": l.line,
+            "column": l.col,
+        })
+    };
+    let messages: Vec<Value> = diags
+        .messages
+        .iter()
+        .map(|msg| {
+            let (code, text) = msg.split_code();
+            json!({
+                "code": code,
+                "severity": severity(msg),
+                "module": msg.module,
+                "element": msg.get_element(),
+                "location": msg.location.as_ref().map(location),
+                "message": text,
+                "labels": msg.labels.iter().map(|(l, label)| {
+                    json!({ "location": location(l), "message": label })
+                }).collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+    serde_json::to_writer_pretty(&mut *output, &messages)?;
+    writeln!(output)
+}
+
+///
+/// Render all messages as SARIF 2.1.0 log.
+///
+pub fn render_sarif(output: &mut impl Write, diags: &Diagnostics) -> std::io::Result<()> {
+    let physical = |l: &SourceLocation| {
+        json!({
+            "artifactLocation": { "uri": l.file },
+            "region": {
+                "startLine": l.line,
+                "startColumn": l.col,
+                "endColumn": l.col + l.len.max(1),
+            },
+        })
+    };
+    let mut rules = BTreeMap::new();
+    let results: Vec<Value> = diags
+        .messages
+        .iter()
+        .map(|msg| {
+            let (code, text) = msg.split_code();
+            let mut result = json!({
+                "level": severity(msg),
+                "message": { "text": text },
+            });
+            if let Some(code) = code {
+                rules.insert(code.to_owned(), json!({ "id": code }));
+                result["ruleId"] = json!(code);
+            }
+            let mut location = json!({});
+            if let Some(l) = &msg.location {
+                location["physicalLocation"] = physical(l);
+            }
+            if let Some(module) = &msg.module {
+                location["logicalLocations"] = json!([{ "name": module, "kind": "module" }]);
+            }
+            if location != json!({}) {
+                result["locations"] = json!([location]);
+            }
+            if !msg.labels.is_empty() {
+                result["relatedLocations"] = msg
+                    .labels
+                    .iter()
+                    .enumerate()
+                    .map(|(id, (l, label))| {
+                        json!({
+                            "id": id,
+                            "physicalLocation": physical(l),
+                            "message": { "text": label },
+                        })
+                    })
+                    .collect();
+            }
+            result
+        })
+        .collect();
+    let log = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules.into_values().collect::<Vec<_>>(),
+                }
+            },
+            "results": results,
+        }],
+    });
+    serde_json::to_writer_pretty(&mut *output, &log)?;
+    writeln!(output)
+}
+
+///
+/// Escape text for XML attributes and content.
+///
+fn escape_xml(input: &str) -> String {
+    input
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+///
+/// Render all messages as JUnit XML.
+///
+/// There is one test suite per module and one test case per message.
+/// Errors are failures, warnings are skipped test cases.
+///
+pub fn render_junit(output: &mut impl Write, diags: &Diagnostics) -> std::io::Result<()> {
+    let mut suites: BTreeMap<&str, Vec<&DiagMsg>> = BTreeMap::new();
+    for msg in &diags.messages {
+        suites
+            .entry(msg.module.as_deref().unwrap_or(env!("CARGO_PKG_NAME")))
+            .or_default()
+            .push(msg);
+    }
+    writeln!(output, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        output,
+        r#"<testsuites name="{}" tests="{}" failures="{}" skipped="{}">"#,
+        env!("CARGO_PKG_NAME"),
+        diags.messages.len(),
+        diags.errors,
+        diags.warnings
+    )?;
+    for (suite, messages) in suites {
+        let failures = messages
+            .iter()
+            .filter(|m| m.diag_type == DiagType::Error)
+            .count();
+        writeln!(
+            output,
+            r#"  <testsuite name="{}" tests="{}" failures="{}" skipped="{}">"#,
+            escape_xml(suite),
+            messages.len(),
+            failures,
+            messages.len() - failures
+        )?;
+        for msg in messages {
+            let (code, text) = msg.split_code();
+            let name = match (&msg.location, code) {
+                (Some(l), Some(code)) => format!("{} {}", code, l),
+                (Some(l), None) => l.to_string(),
+                (None, Some(code)) => code.to_owned(),
+                (None, None) => severity(msg).to_owned(),
+            };
+            writeln!(
+                output,
+                r#"    <testcase name="{}" classname="{}">"#,
+                escape_xml(&name),
+                escape_xml(suite)
+            )?;
+            match msg.diag_type {
+                DiagType::Error => writeln!(
+                    output,
+                    r#"      <failure message="{}" type="{}">{}</failure>"#,
+                    escape_xml(text),
+                    code.unwrap_or("error"),
+                    escape_xml(&msg.to_string())
+                )?,
+                DiagType::Warning => writeln!(
+                    output,
+                    r#"      <skipped message="{}"/>"#,
+                    escape_xml(&msg.to_string())
+                )?,
+            }
+            writeln!(output, "    </testcase>")?;
+        }
+        writeln!(output, "  </testsuite>")?;
+    }
+    writeln!(output, "</testsuites>")
+}
+
+///
+/// Escape data of GitHub workflow commands.
+///
+fn escape_github_data(input: &str) -> String {
+    input
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+///
+/// Escape properties of GitHub workflow commands.
+///
+fn escape_github_property(input: &str) -> String {
+    escape_github_data(input)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+///
+/// Render all messages as GitHub workflow commands,
+/// such that they are shown as annotations.
+///
+pub fn render_github(output: &mut impl Write, diags: &Diagnostics) -> std::io::Result<()> {
+    for msg in &diags.messages {
+        let (code, text) = msg.split_code();
+        let mut properties = Vec::new();
+        if let Some(l) = &msg.location {
+            properties.push(format!("file={}", escape_github_property(&l.file)));
+            properties.push(format!("line={}", l.line));
+            properties.push(format!("col={}", l.col));
+            properties.push(format!("endColumn={}", l.col + l.len.max(1)));
+        }
+        if let Some(code) = code {
+            properties.push(format!("title={}", code));
+        }
+        let text = match &msg.module {
+            Some(module) => format!("({}) {}", module, text),
+            None => text.to_owned(),
+        };
+        let command = if properties.is_empty() {
+            severity(msg).to_owned()
+        } else {
+            format!("{} {}", severity(msg), properties.join(","))
+        };
+        writeln!(output, "::{}::{}", command, escape_github_data(&text))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn diags() -> Diagnostics {
+        let mut d = Diagnostics::default();
+        let location = SourceLocation {
+            file: "main.gsn.yaml".to_owned(),
+            line: 3,
+            col: 21,
+            len: 2,
+        };
+        d.add_located_msg(
+            DiagType::Error,
+            Some("main"),
+            Some("G1"),
+            Some(&location),
+            vec![(location.to_owned(), "here".to_owned())],
+            "C03: Element G1 has unresolved supported by element: G3".to_owned(),
+        );
+        d.add_warning(Some("main"), "C05: Module <main> is odd".to_owned());
+        d.add_warning(None, "Unknown".to_owned());
+        d
+    }
+
+    fn render(f: fn(&mut Vec<u8>, &Diagnostics) -> std::io::Result<()>) -> String {
+        let mut output = Vec::new();
+        f(&mut output, &diags()).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn json() {
+        let output: Value = serde_json::from_str(&render(render_json)).unwrap();
+        assert_eq!(output[0]["code"], "C03");
+        assert_eq!(output[0]["severity"], "error");
+        assert_eq!(output[0]["module"], "main");
+        assert_eq!(output[0]["element"], "G1");
+        assert_eq!(output[0]["location"]["line"], 3);
+        assert_eq!(output[0]["location"]["column"], 21);
+        assert_eq!(output[0]["labels"][0]["message"], "here");
+        assert_eq!(output[1]["severity"], "warning");
+        assert_eq!(output[1]["location"], Value::Null);
+        assert_eq!(output[2]["code"], Value::Null);
+    }
+
+    #[test]
+    fn sarif() {
+        let output: Value = serde_json::from_str(&render(render_sarif)).unwrap();
+        assert_eq!(output["version"], "2.1.0");
+        let run = &output["runs"][0];
+        assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "C03");
+        assert_eq!(run["tool"]["driver"]["rules"][1]["id"], "C05");
+        let result = &run["results"][0];
+        assert_eq!(result["ruleId"], "C03");
+        assert_eq!(result["level"], "error");
+        let location = &result["locations"][0];
+        assert_eq!(
+            location["physicalLocation"]["artifactLocation"]["uri"],
+            "main.gsn.yaml"
+        );
+        assert_eq!(location["physicalLocation"]["region"]["endColumn"], 23);
+        assert_eq!(location["logicalLocations"][0]["name"], "main");
+        assert_eq!(result["relatedLocations"][0]["message"]["text"], "here");
+        assert!(run["results"][1]["locations"][0]["physicalLocation"].is_null());
+        assert!(run["results"][2]["locations"].is_null());
+    }
+
+    #[test]
+    fn junit() {
+        let output = render(render_junit);
+        assert!(output.contains(r#"<testsuites name="gsn2x" tests="3" failures="1" skipped="2">"#));
+        assert!(output.contains(r#"<testsuite name="main" tests="2" failures="1" skipped="1">"#));
+        assert!(output.contains(r#"<testcase name="warning" classname="gsn2x">"#));
+        assert!(output.contains(r#"<testcase name="C03 main.gsn.yaml:3:21" classname="main">"#));
+        assert!(output.contains(
+            r#"<failure message="Element G1 has unresolved supported by element: G3" type="C03">"#
+        ));
+        assert!(output
+            .contains(r#"<skipped message="Warning: (main) C05: Module &lt;main&gt; is odd"/>"#));
+    }
+
+    #[test]
+    fn github() {
+        assert_eq!(
+            render(render_github),
+            "::error file=main.gsn.yaml,line=3,col=21,endColumn=23,title=C03::(main) Element G1 has unresolved supported by element: G3\n\
+             ::warning title=C05::(main) Module <main> is odd\n\
+             ::warning::Unknown\n"
+        );
+        assert_eq!(escape_github_property("a:b,c%\n"), "a%3Ab%2Cc%25%0A");
+    }
+}
@@ -5,6 +5,10 @@ pub enum SingleEdge {
     InContextOf,
     SupportedBy,
     Composite,
+    /// Challenges relationship of the dialectic extension.
+    /// It is reversed i.e., the target challenges the source,
+    /// such that challenging elements are placed below the challenged element.
+    Challenges,
 }
 
 impl BitOr for SingleEdge {
@@ -27,6 +31,13 @@ impl BitOr for SingleEdge {
                 }
             }
             SingleEdge::Composite => SingleEdge::Composite,
+            SingleEdge::Challenges => {
+                if rhs == SingleEdge::Challenges {
+                    SingleEdge::Challenges
+                } else {
+                    SingleEdge::Composite
+                }
+            }
         }
     }
 }
@@ -38,6 +49,24 @@ pub enum EdgeType {
     // Invisible,
 }
 
+///
+/// Decorations of an edge from the pattern extension of GSN.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum EdgeDecorator {
+    /// Multiplicity with its label e.g., "n" or "at least 2" (solid dot)
+    Multiple(String),
+    /// Optionality (hollow dot)
+    Optional,
+    /// Choice with an optional label e.g., "1 of 3" (diamond at the source)
+    Choice(Option<String>),
+    /// Assurance Claim Point with its name and the link to its confidence argument
+    Acp(String, Option<String>),
+    /// The challenges relationship targets the link from the source to the given element
+    /// instead of the source itself.
+    ChallengedLink(String),
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -433,6 +433,7 @@ fn find_next_child_node<'a, 'b>(
             .filter_map(|(id, et)| match et {
                 EdgeType::OneWay(SingleEdge::SupportedBy)
                 | EdgeType::OneWay(SingleEdge::Composite)
+                | EdgeType::OneWay(SingleEdge::Challenges)
                 | EdgeType::TwoWay((_, SingleEdge::SupportedBy))
                 | EdgeType::TwoWay((SingleEdge::SupportedBy, _))
                 | EdgeType::TwoWay((_, SingleEdge::Composite))
@@ -2,15 +2,21 @@ pub mod edges;
 mod graph;
 pub mod nodes;
 mod util;
-use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    rc::Rc,
+};
 pub use util::{escape_node_id, escape_text};
 
-use edges::{EdgeType, SingleEdge};
+use edges::{EdgeDecorator, EdgeType, SingleEdge};
 use graph::{rank_nodes, NodePlace};
-use nodes::{setup_basics, Node, Port};
+use nodes::{render_acp, render_markers, setup_basics, Node, NodeMarker, Port};
 use rusttype::Font;
 use svg::{
-    node::element::{path::Data, Link, Marker, Path, Polyline, Rectangle, Symbol, Text, Title},
+    node::element::{
+        path::Data, Circle, Group, Link, Marker, Path, Polyline, Rectangle, Symbol, Text, Title,
+    },
     Document,
 };
 use util::{
@@ -21,6 +27,8 @@ use util::{
 use self::graph::calculate_parent_edge_map;
 
 const MARKER_HEIGHT: u32 = 10;
+const CHOICE_SIZE: i32 = 6;
+const MULTIPLICITY_RADIUS: i32 = 4;
 
 pub struct Margin {
     pub top: i32,
@@ -56,6 +64,8 @@ pub struct DirGraph<'a> {
     forced_levels: BTreeMap<&'a str, Vec<&'a str>>,
     nodes: BTreeMap<String, Rc<RefCell<dyn Node>>>,
     edges: BTreeMap<String, Vec<(String, EdgeType)>>,
+    node_markers: BTreeMap<String, Vec<NodeMarker>>,
+    edge_decorators: BTreeMap<(String, String), Vec<EdgeDecorator>>,
     document: Document,
     meta_information: Option<Vec<String>>,
 }
@@ -76,6 +86,8 @@ impl<'a> Default for DirGraph<'a> {
             forced_levels: BTreeMap::new(),
             nodes: BTreeMap::new(),
             edges: BTreeMap::new(),
+            node_markers: BTreeMap::new(),
+            edge_decorators: BTreeMap::new(),
             document: Document::new(),
             meta_information: None,
         }
@@ -149,6 +161,19 @@ impl<'a> DirGraph<'a> {
         self
     }
 
+    pub fn add_node_markers(mut self, markers: &mut BTreeMap<String, Vec<NodeMarker>>) -> Self {
+        self.node_markers.append(markers);
+        self
+    }
+
+    pub fn add_edge_decorators(
+        mut self,
+        decorators: &mut BTreeMap<(String, String), Vec<EdgeDecorator>>,
+    ) -> Self {
+        self.edge_decorators.append(decorators);
+        self
+    }
+
     pub fn add_meta_information(mut self, meta: &mut Vec<String>) -> Self {
         self.meta_information.get_or_insert(Vec::new()).append(meta);
         self
@@ -253,13 +278,13 @@ impl<'a> DirGraph<'a> {
             for np in rank.values() {
                 match np {
                     NodePlace::Node(id) => {
-                        let mut n = self.nodes.get(id).unwrap().borrow_mut();
-                        self.document = self.document.add(n.render(&self.font));
+                        let element = self.render_node(id);
+                        self.document = self.document.add(element);
                     }
                     NodePlace::MultipleNodes(ids) => {
                         for id in ids {
-                            let mut n = self.nodes.get(id).unwrap().borrow_mut();
-                            self.document = self.document.add(n.render(&self.font));
+                            let element = self.render_node(id);
+                            self.document = self.document.add(element);
                         }
                     }
                 }
@@ -281,6 +306,18 @@ impl<'a> DirGraph<'a> {
         self
     }
 
+    ///
+    /// Render a single node including its markers
+    ///
+    fn render_node(&self, id: &str) -> svg::node::element::Element {
+        let mut n = self.nodes.get(id).unwrap().borrow_mut();
+        let mut element = n.render(&self.font);
+        if let Some(markers) = self.node_markers.get(id) {
+            render_markers(&mut element, &*n, markers, &self.font);
+        }
+        element
+    }
+
     ///
     ///
     ///
@@ -359,6 +396,7 @@ impl<'a> DirGraph<'a> {
                             EdgeType::OneWay(SingleEdge::SupportedBy)
                                 | EdgeType::TwoWay((_, SingleEdge::SupportedBy))
                                 | EdgeType::OneWay(SingleEdge::Composite)
+                                | EdgeType::OneWay(SingleEdge::Challenges)
                                 | EdgeType::TwoWay((_, SingleEdge::Composite))
                         )
                     })
@@ -377,6 +415,7 @@ impl<'a> DirGraph<'a> {
                                     EdgeType::OneWay(SingleEdge::SupportedBy)
                                         | EdgeType::TwoWay((_, SingleEdge::SupportedBy))
                                         | EdgeType::OneWay(SingleEdge::Composite)
+                                        | EdgeType::OneWay(SingleEdge::Challenges)
                                         | EdgeType::TwoWay((_, SingleEdge::Composite))
                                 )
                             })
@@ -439,6 +478,7 @@ impl<'a> DirGraph<'a> {
                         EdgeType::OneWay(SingleEdge::SupportedBy)
                         | EdgeType::TwoWay((_, SingleEdge::SupportedBy))
                         | EdgeType::OneWay(SingleEdge::Composite)
+                        | EdgeType::OneWay(SingleEdge::Challenges)
                         | EdgeType::TwoWay((_, SingleEdge::Composite)) => Some(c.as_str()),
                         _ => None,
                     })
@@ -458,6 +498,7 @@ impl<'a> DirGraph<'a> {
                                     EdgeType::OneWay(SingleEdge::SupportedBy)
                                         | EdgeType::TwoWay((_, SingleEdge::SupportedBy))
                                         | EdgeType::OneWay(SingleEdge::Composite)
+                                        | EdgeType::OneWay(SingleEdge::Challenges)
                                         | EdgeType::TwoWay((_, SingleEdge::Composite))
                                 )
                             })
@@ -493,6 +534,7 @@ impl<'a> DirGraph<'a> {
                                             EdgeType::OneWay(SingleEdge::SupportedBy)
                                                 | EdgeType::TwoWay((_, SingleEdge::SupportedBy))
                                                 | EdgeType::OneWay(SingleEdge::Composite)
+                                                | EdgeType::OneWay(SingleEdge::Challenges)
                                                 | EdgeType::TwoWay((_, SingleEdge::Composite))
                                         )
                                     })
@@ -544,6 +586,81 @@ impl<'a> DirGraph<'a> {
             .unwrap()
     }
 
+    ///
+    /// Get the start and end points of an edge, their support points for the curve
+    /// and the direction in which the edge leaves the source.
+    ///
+    fn get_edge_points(
+        &self,
+        source: &str,
+        target: &str,
+        edge_type: &EdgeType,
+    ) -> (Point2D, Point2D, Point2D, Point2D, (i32, i32)) {
+        let s = self.nodes.get(source).unwrap().borrow();
+        let t = self.nodes.get(target).unwrap().borrow();
+        let (marker_start_height, marker_end_height, support_distance) = match edge_type {
+            // EdgeType::Invisible => (0i32, 0i32, 3i32 * MARKER_HEIGHT as i32),
+            EdgeType::OneWay(SingleEdge::Challenges) => {
+                (MARKER_HEIGHT as i32, 0i32, 3i32 * MARKER_HEIGHT as i32)
+            }
+            EdgeType::OneWay(_) => (0i32, MARKER_HEIGHT as i32, 3i32 * MARKER_HEIGHT as i32),
+            EdgeType::TwoWay(_) => (
+                MARKER_HEIGHT as i32,
+                MARKER_HEIGHT as i32,
+                3i32 * MARKER_HEIGHT as i32,
+            ),
+        };
+        let s_pos = s.get_position();
+        let t_pos = t.get_position();
+        if s_pos.y + s.get_height() / 2 < t_pos.y - t.get_height() / 2 {
+            (
+                s.get_coordinates(&Port::South)
+                    .move_relative(0, marker_start_height),
+                s.get_coordinates(&Port::South)
+                    .move_relative(0, support_distance),
+                t.get_coordinates(&Port::North)
+                    .move_relative(0, -marker_end_height),
+                t.get_coordinates(&Port::North)
+                    .move_relative(0, -support_distance),
+                (0, 1),
+            )
+        } else if s_pos.y - s.get_height() / 2 - self.margin.top > t_pos.y + t.get_height() / 2 {
+            (
+                s.get_coordinates(&Port::North)
+                    .move_relative(0, -marker_start_height),
+                s.get_coordinates(&Port::North)
+                    .move_relative(0, -support_distance),
+                t.get_coordinates(&Port::South)
+                    .move_relative(0, marker_end_height),
+                t.get_coordinates(&Port::South)
+                    .move_relative(0, support_distance),
+                (0, -1),
+            )
+        } else if s_pos.x - s.get_width() / 2 > t_pos.x + t.get_width() / 2 {
+            (
+                s.get_coordinates(&Port::West)
+                    .move_relative(-marker_start_height, 0),
+                s.get_coordinates(&Port::West),
+                t.get_coordinates(&Port::East)
+                    .move_relative(marker_end_height, 0),
+                t.get_coordinates(&Port::East)
+                    .move_relative(support_distance, 0),
+                (-1, 0),
+            )
+        } else {
+            (
+                s.get_coordinates(&Port::East)
+                    .move_relative(marker_start_height, 0),
+                s.get_coordinates(&Port::East),
+                t.get_coordinates(&Port::West)
+                    .move_relative(-marker_end_height, 0),
+                t.get_coordinates(&Port::West)
+                    .move_relative(-support_distance, 0),
+                (1, 0),
+            )
+        }
+    }
+
     ///
     /// Render the edges
     ///
@@ -551,69 +668,79 @@ impl<'a> DirGraph<'a> {
     ///
     ///
     fn render_edges(mut self) -> Self {
+        let mut choices = BTreeSet::<&str>::new();
         for (source, targets) in &self.edges {
             for (target, edge_type) in targets {
-                let s = self.nodes.get(source).unwrap().borrow();
-                let t = self.nodes.get(target).unwrap().borrow();
-                let (marker_start_height, marker_end_height, support_distance) = match edge_type {
-                    // EdgeType::Invisible => (0i32, 0i32, 3i32 * MARKER_HEIGHT as i32),
-                    EdgeType::OneWay(_) => {
-                        (0i32, MARKER_HEIGHT as i32, 3i32 * MARKER_HEIGHT as i32)
+                let (start, start_sup, end, end_sup, (dx, dy)) =
+                    self.get_edge_points(source, target, edge_type);
+                let decorators = self
+                    .edge_decorators
+                    .get(&(source.to_owned(), target.to_owned()))
+                    .map(|d| d.as_slice())
+                    .unwrap_or_default();
+                // A challenged link is targeted in its middle.
+                let (start, start_sup) = match decorators.iter().find_map(|d| match d {
+                    EdgeDecorator::ChallengedLink(link_target) => self
+                        .edges
+                        .get(source)
+                        .and_then(|e| e.iter().find(|(t, _)| t == link_target)),
+                    _ => None,
+                }) {
+                    Some((link_target, link_type)) => {
+                        let (l_start, l_start_sup, l_end, l_end_sup, _) =
+                            self.get_edge_points(source, link_target, link_type);
+                        let middle = Point2D {
+                            x: (l_start.x + 3 * l_start_sup.x + 3 * l_end_sup.x + l_end.x) / 8,
+                            y: (l_start.y + 3 * l_start_sup.y + 3 * l_end_sup.y + l_end.y) / 8,
+                        };
+                        (middle, middle)
                     }
-                    EdgeType::TwoWay(_) => (
-                        MARKER_HEIGHT as i32,
-                        MARKER_HEIGHT as i32,
-                        3i32 * MARKER_HEIGHT as i32,
-                    ),
+                    None => (start, start_sup),
                 };
-                let s_pos = s.get_position();
-                let t_pos = t.get_position();
-                let (start, start_sup, end, end_sup) =
-                    if s_pos.y + s.get_height() / 2 < t_pos.y - t.get_height() / 2 {
-                        (
-                            s.get_coordinates(&Port::South)
-                                .move_relative(0, marker_start_height),
-                            s.get_coordinates(&Port::South)
-                                .move_relative(0, support_distance),
-                            t.get_coordinates(&Port::North)
-                                .move_relative(0, -marker_end_height),
-                            t.get_coordinates(&Port::North)
-                                .move_relative(0, -support_distance),
-                        )
-                    } else if s_pos.y - s.get_height() / 2 - self.margin.top
-                        > t_pos.y + t.get_height() / 2
-                    {
-                        (
-                            s.get_coordinates(&Port::North)
-                                .move_relative(0, -marker_start_height),
-                            s.get_coordinates(&Port::North)
-                                .move_relative(0, -support_distance),
-                            t.get_coordinates(&Port::South)
-                                .move_relative(0, marker_end_height),
-                            t.get_coordinates(&Port::South)
-                                .move_relative(0, support_distance),
-                        )
-                    } else if s_pos.x - s.get_width() / 2 > t_pos.x + t.get_width() / 2 {
-                        (
-                            s.get_coordinates(&Port::West)
-                                .move_relative(-marker_start_height, 0),
-                            s.get_coordinates(&Port::West),
-                            t.get_coordinates(&Port::East)
-                                .move_relative(marker_end_height, 0),
-                            t.get_coordinates(&Port::East)
-                                .move_relative(support_distance, 0),
-                        )
-                    } else {
-                        (
-                            s.get_coordinates(&Port::East)
-                                .move_relative(marker_start_height, 0),
-                            s.get_coordinates(&Port::East),
-                            t.get_coordinates(&Port::West)
-                                .move_relative(-marker_end_height, 0),
-                            t.get_coordinates(&Port::West)
-                                .move_relative(-support_distance, 0),
-                        )
+                // A choice is drawn as a diamond at the source, all edges of the choice start from there.
+                let start = match decorators.iter().find_map(|d| match d {
+                    EdgeDecorator::Choice(label) => Some(label),
+                    _ => None,
+                }) {
+                    Some(label) => {
+                        if choices.insert(source) {
+                            let center = start.move_relative(dx * CHOICE_SIZE, dy * CHOICE_SIZE);
+                            let choice = render_choice(&center, label, &self.font);
+                            self.document = self.document.add(choice);
+                        }
+                        start.move_relative(2 * dx * CHOICE_SIZE, 2 * dy * CHOICE_SIZE)
+                    }
+                    None => start,
+                };
+                for decorator in decorators {
+                    // Multiplicities are drawn in the middle of the edge.
+                    let middle = Point2D {
+                        x: (start.x + 3 * start_sup.x + 3 * end_sup.x + end.x) / 8,
+                        y: (start.y + 3 * start_sup.y + 3 * end_sup.y + end.y) / 8,
                     };
+                    let multiplicity = match decorator {
+                        EdgeDecorator::Multiple(label) => {
+                            Some(render_multiplicity(&middle, true, Some(label), &self.font))
+                        }
+                        EdgeDecorator::Optional => {
+                            Some(render_multiplicity(&middle, false, None, &self.font))
+                        }
+                        EdgeDecorator::Choice(_)
+                        | EdgeDecorator::Acp(_, _)
+                        | EdgeDecorator::ChallengedLink(_) => None,
+                    };
+                    if let Some(multiplicity) = multiplicity {
+                        self.document = self.document.add(multiplicity);
+                    }
+                    // ACPs are drawn at three quarters of the edge.
+                    if let EdgeDecorator::Acp(name, url) = decorator {
+                        let pos = Point2D {
+                            x: (start.x + 9 * start_sup.x + 27 * end_sup.x + 27 * end.x) / 64,
+                            y: (start.y + 9 * start_sup.y + 27 * end_sup.y + 27 * end.y) / 64,
+                        };
+                        self.document = self.document.add(render_acp(&pos, name, url, &self.font));
+                    }
+                }
                 let parameters = (start_sup.x, start_sup.y, end_sup.x, end_sup.y, end.x, end.y);
                 let data = Data::new()
                     .move_to((start.x, start.y))
@@ -629,6 +756,8 @@ impl<'a> DirGraph<'a> {
                     }
                     EdgeType::OneWay(SingleEdge::Composite)
                     | EdgeType::TwoWay((_, SingleEdge::Composite)) => Some("url(#composite_arrow)"),
+                    EdgeType::OneWay(SingleEdge::Challenges)
+                    | EdgeType::TwoWay((_, SingleEdge::Challenges)) => None,
                     // EdgeType::Invisible => None,
                 };
                 let arrow_start_id = match &edge_type {
@@ -639,6 +768,10 @@ impl<'a> DirGraph<'a> {
                         Some("url(#supportedby_arrow)")
                     }
                     EdgeType::TwoWay((SingleEdge::Composite, _)) => Some("url(#composite_arrow)"),
+                    EdgeType::OneWay(SingleEdge::Challenges)
+                    | EdgeType::TwoWay((SingleEdge::Challenges, _)) => {
+                        Some("url(#supportedby_arrow)")
+                    }
                     _ => None,
                 };
                 let mut classes = "gsnedge".to_string();
@@ -658,6 +791,12 @@ impl<'a> DirGraph<'a> {
                         // Already covered by all other matches
                         //| EdgeType::TwoWay((SingleEdge::Composite, _))
                         classes.push_str(" gsncomposite")
+                    }
+                    EdgeType::OneWay(SingleEdge::Challenges)
+                    | EdgeType::TwoWay((_, SingleEdge::Challenges)) => {
+                        // Already covered by all other matches
+                        //| EdgeType::TwoWay((SingleEdge::Challenges, _))
+                        classes.push_str(" gsnchallenges")
                     } // EdgeType::Invisible => classes.push_str(" gsninvis"),
                 };
                 let mut e = Path::new()
@@ -671,6 +810,9 @@ impl<'a> DirGraph<'a> {
                 if let Some(arrow_id) = arrow_start_id {
                     e = e.set("marker-start", arrow_id);
                 }
+                if let EdgeType::OneWay(SingleEdge::Challenges) = edge_type {
+                    e = e.set("stroke-dasharray", "5,3");
+                }
                 e = e.set("class", classes);
                 self.document = self.document.add(e);
             }
@@ -839,6 +981,73 @@ impl<'a> DirGraph<'a> {
     }
 }
 
+///
+/// Render the diamond of a choice and its label.
+///
+fn render_choice(center: &Point2D, label: &Option<String>, font: &FontInfo) -> Group {
+    let data = Data::new()
+        .move_to((center.x, center.y - CHOICE_SIZE))
+        .line_by((CHOICE_SIZE, CHOICE_SIZE))
+        .line_by((-CHOICE_SIZE, CHOICE_SIZE))
+        .line_by((-CHOICE_SIZE, -CHOICE_SIZE))
+        .close();
+    let mut g = Group::new().set("class", "gsnchoice").add(
+        Path::new()
+            .set("fill", "none")
+            .set("stroke", "black")
+            .set("stroke-width", 1u32)
+            .set("d", data),
+    );
+    if let Some(label) = label {
+        g = g.add(render_edge_label(
+            center.move_relative(CHOICE_SIZE + 3, 4),
+            label,
+            font,
+        ));
+    }
+    g
+}
+
+///
+/// Render the dot of a multiplicity (solid) or an optionality (hollow) and its label.
+///
+fn render_multiplicity(
+    center: &Point2D,
+    solid: bool,
+    label: Option<&String>,
+    font: &FontInfo,
+) -> Group {
+    let mut g = Group::new().set("class", "gsnmultiplicity").add(
+        Circle::new()
+            .set("cx", center.x)
+            .set("cy", center.y)
+            .set("r", MULTIPLICITY_RADIUS)
+            .set("fill", if solid { "black" } else { "white" })
+            .set("stroke", "black")
+            .set("stroke-width", 1u32),
+    );
+    if let Some(label) = label {
+        g = g.add(render_edge_label(
+            center.move_relative(MULTIPLICITY_RADIUS + 3, 4),
+            label,
+            font,
+        ));
+    }
+    g
+}
+
+///
+/// Render a label next to an edge.
+///
+fn render_edge_label(pos: Point2D, label: &str, font: &FontInfo) -> Text {
+    Text::new()
+        .set("x", pos.x)
+        .set("y", pos.y)
+        .set("font-size", font.size)
+        .set("font-family", font.name.as_str())
+        .add(svg::node::Text::new(label))
+}
+
 #[cfg(test)]
 mod test {
     use super::{nodes::new_away_goal, *};
@@ -873,4 +1082,19 @@ mod test {
         d.write(&mut string_buffer, false).unwrap();
         println!("{}", std::str::from_utf8(string_buffer.as_slice()).unwrap());
     }
+
+    #[test]
+    fn test_render_public_marker() {
+        let mut d = DirGraph::default();
+        let b1 = nodes::new_goal("G1", "text", false, None, None);
+        let mut nodes = BTreeMap::new();
+        nodes.insert("G1".to_owned(), b1 as Rc<RefCell<dyn Node>>);
+        let mut markers = BTreeMap::new();
+        markers.insert("G1".to_owned(), vec![NodeMarker::Public]);
+        d = d.add_nodes(nodes).add_node_markers(&mut markers);
+        let mut string_buffer = Vec::new();
+        d.write(&mut string_buffer, false).unwrap();
+        let output = std::str::from_utf8(string_buffer.as_slice()).unwrap();
+        assert!(output.contains("class=\"gsnpublic\""));
+    }
 }
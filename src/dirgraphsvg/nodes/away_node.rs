@@ -8,9 +8,11 @@ const PADDING_VERTICAL: i32 = 7;
 const PADDING_HORIZONTAL: i32 = 7;
 const TEXT_OFFSET: i32 = 20;
 const MODULE_IMAGE: i32 = 20;
+const STRATEGY_SKEW: i32 = 15;
 
 pub enum AwayType {
     Goal,
+    Strategy,
     Solution,
     Context,
     Assumption,
@@ -66,7 +68,7 @@ impl Node for AwayNode {
         self.mod_height = mod_height;
         self.width = *[
             self.width,
-            text_width,
+            text_width + self.get_skew() * 2,
             mod_width + MODULE_IMAGE + PADDING_HORIZONTAL,
         ]
         .iter()
@@ -79,6 +81,7 @@ impl Node for AwayNode {
         ); // +3 to make padding at bottom larger
         self.addon_height = match self.node_type {
             AwayType::Goal => 0,
+            AwayType::Strategy => 0,
             AwayType::Solution => (self.width as f32 * 0.5) as i32,
             AwayType::Context => (self.width as f32 * 0.1) as i32,
             AwayType::Assumption => (self.width as f32 * 0.25) as i32,
@@ -129,6 +132,14 @@ impl Node for AwayNode {
                 .vertical_line_to(self.y - self.height / 2)
                 .horizontal_line_to(self.x + self.width / 2)
                 .vertical_line_to(start_y),
+            AwayType::Strategy => Data::new()
+                .move_to((self.x - self.width / 2, start_y))
+                .line_to((
+                    self.x - self.width / 2 + STRATEGY_SKEW,
+                    self.y - self.height / 2,
+                ))
+                .horizontal_line_to(self.x + self.width / 2)
+                .line_to((self.x + self.width / 2 - STRATEGY_SKEW, start_y)),
             AwayType::Solution | AwayType::Assumption | AwayType::Justification => Data::new()
                 .move_to((self.x - self.width / 2, start_y))
                 .vertical_line_to(self.y - self.height / 2 + self.addon_height)
@@ -197,7 +208,10 @@ impl Node for AwayNode {
             .add(svg::node::Text::new(&self.module));
 
         let id = Text::new()
-            .set("x", self.x - self.width / 2 + PADDING_HORIZONTAL)
+            .set(
+                "x",
+                self.x - self.width / 2 + PADDING_HORIZONTAL + self.get_skew(),
+            )
             .set("y", start_id + self.lines.get(0).unwrap().1)
             .set("textLength", self.lines.get(0).unwrap().0)
             .set("font-weight", "bold")
@@ -248,7 +262,10 @@ impl Node for AwayNode {
 
         for (n, t) in self.text.lines().enumerate() {
             let text = Text::new()
-                .set("x", self.x - self.width / 2 + PADDING_HORIZONTAL)
+                .set(
+                    "x",
+                    self.x - self.width / 2 + PADDING_HORIZONTAL + self.get_skew(),
+                )
                 .set(
                     "y",
                     start_id + TEXT_OFFSET + (n as i32 + 1) * self.lines.get(n + 1).unwrap().1,
@@ -265,6 +282,16 @@ impl Node for AwayNode {
 }
 
 impl AwayNode {
+    ///
+    /// Only strategies are skewed.
+    ///
+    fn get_skew(&self) -> i32 {
+        match self.node_type {
+            AwayType::Strategy => STRATEGY_SKEW,
+            _ => 0,
+        }
+    }
+
     pub fn new(
         id: &str,
         text: &str,
@@ -1,6 +1,6 @@
 use std::{cell::RefCell, rc::Rc};
 
-use svg::node::element::{Element, Group, Link};
+use svg::node::element::{path::Data, Element, Group, Link, Path, Rectangle, Text, Use};
 
 use crate::dirgraphsvg::{util::point2d::Point2D, FontInfo};
 
@@ -26,6 +26,30 @@ pub enum Port {
     West,
 }
 
+const MARKER_SIZE: i32 = 20;
+const MARKER_PADDING: i32 = 5;
+const ACP_SIZE: i32 = 8;
+
+///
+/// Markers that are drawn on top of a node in addition to its shape.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum NodeMarker {
+    /// Public indicator of the modular extension
+    Public,
+    /// Uninstantiated element of the pattern extension (hollow triangle)
+    Uninstantiated,
+    /// Undeveloped and uninstantiated element of the pattern extension
+    /// (hollow triangle within the undeveloped diamond)
+    UndevelopedUninstantiated,
+    /// Assurance Claim Point with its name and the link to its confidence argument
+    Acp(String, Option<String>),
+    /// Baconian confidence score of the dialectic extension e.g., `1|2`
+    Score(String),
+    /// Propagated confidence as badge at the upper left corner e.g., `0.72`
+    Confidence(String),
+}
+
 pub trait Node {
     fn get_id(&self) -> &str;
     fn calculate_size(&mut self, font: &FontInfo, suggested_char_wrap: u32);
@@ -86,6 +110,159 @@ pub(crate) fn setup_basics(
     }
 }
 
+///
+/// Append markers to an already rendered node.
+/// They are placed relative to the bounding box of the node.
+///
+pub(crate) fn render_markers(
+    element: &mut Element,
+    node: &dyn Node,
+    markers: &[NodeMarker],
+    font: &FontInfo,
+) {
+    use svg::Node;
+    let pos = node.get_position();
+    for marker in markers {
+        match marker {
+            NodeMarker::Public => element.append(
+                Use::new()
+                    .set("href", "#module_icon")
+                    .set("class", "gsnpublic")
+                    .set(
+                        "x",
+                        pos.x + node.get_width() / 2 - MARKER_SIZE - MARKER_PADDING,
+                    )
+                    .set("y", pos.y - node.get_height() / 2 + MARKER_PADDING),
+            ),
+            NodeMarker::Uninstantiated => element.append(render_triangle(
+                Point2D {
+                    x: pos.x,
+                    y: pos.y + node.get_height() / 2,
+                },
+                10,
+            )),
+            NodeMarker::UndevelopedUninstantiated => element.append(render_triangle(
+                Point2D {
+                    x: pos.x,
+                    y: pos.y + node.get_height() / 2 + 2,
+                },
+                5,
+            )),
+            NodeMarker::Acp(name, url) => element.append(render_acp(
+                &Point2D {
+                    x: pos.x + node.get_width() / 2,
+                    y: pos.y,
+                },
+                name,
+                url,
+                font,
+            )),
+            NodeMarker::Score(score) => element.append(
+                Text::new()
+                    .set("class", "gsnscore")
+                    .set("x", pos.x + node.get_width() / 2 - MARKER_PADDING)
+                    .set("y", pos.y + node.get_height() / 2 - MARKER_PADDING)
+                    .set("text-anchor", "end")
+                    .set("font-size", font.size)
+                    .set("font-family", font.name.as_str())
+                    .add(svg::node::Text::new(score)),
+            ),
+            NodeMarker::Confidence(confidence) => element.append(render_badge(
+                &Point2D {
+                    x: pos.x - node.get_width() / 2,
+                    y: pos.y - node.get_height() / 2,
+                },
+                confidence,
+                font,
+            )),
+        }
+    }
+}
+
+///
+/// Render a badge with the given text, whose left edge is vertically centered at `pos`.
+///
+fn render_badge(pos: &Point2D, text: &str, font: &FontInfo) -> Element {
+    let (width, height) =
+        crate::dirgraphsvg::util::font::text_bounding_box(&font.font, text, font.size);
+    Group::new()
+        .set("class", "gsnconfidence")
+        .add(
+            Rectangle::new()
+                .set("x", pos.x)
+                .set("y", pos.y - height / 2 - MARKER_PADDING)
+                .set("width", width + 2 * MARKER_PADDING)
+                .set("height", height + 2 * MARKER_PADDING)
+                .set("rx", MARKER_PADDING)
+                .set("fill", "white")
+                .set("stroke", "black")
+                .set("stroke-width", 1u32),
+        )
+        .add(
+            Text::new()
+                .set("x", pos.x + MARKER_PADDING)
+                .set("y", pos.y + height / 2)
+                .set("font-size", font.size)
+                .set("font-family", font.name.as_str())
+                .add(svg::node::Text::new(text)),
+        )
+        .into()
+}
+
+///
+/// Render an Assurance Claim Point centered at `pos` with its name.
+/// If the ACP has a confidence argument, it is linked to it.
+///
+pub(crate) fn render_acp(
+    pos: &Point2D,
+    name: &str,
+    url: &Option<String>,
+    font: &FontInfo,
+) -> Element {
+    let g = Group::new()
+        .set("class", "gsnacp")
+        .add(
+            Rectangle::new()
+                .set("x", pos.x - ACP_SIZE / 2)
+                .set("y", pos.y - ACP_SIZE / 2)
+                .set("width", ACP_SIZE)
+                .set("height", ACP_SIZE)
+                .set("fill", "black")
+                .set("stroke", "black")
+                .set("stroke-width", 1u32),
+        )
+        .add(
+            Text::new()
+                .set("x", pos.x + ACP_SIZE)
+                .set("y", pos.y - ACP_SIZE / 2)
+                .set("font-size", font.size)
+                .set("font-family", font.name.as_str())
+                .add(svg::node::Text::new(name)),
+        );
+    if let Some(url) = url {
+        Link::new().set("xlink:href", url.as_str()).add(g).into()
+    } else {
+        g.into()
+    }
+}
+
+///
+/// Render a hollow triangle with its top at `top`.
+///
+fn render_triangle(top: Point2D, height: i32) -> Path {
+    let data = Data::new()
+        .move_to((top.x, top.y))
+        .line_by((height / 2, height))
+        .line_by((-height, 0))
+        .close();
+    Path::new()
+        .set("class", "gsnuninst")
+        .set("fill", "none")
+        .set("stroke", "black")
+        .set("stroke-width", 1u32)
+        .set("d", data)
+}
+
 ///
 ///
 ///
@@ -266,6 +443,32 @@ pub fn new_strategy(
     )))
 }
 
+///
+/// Create a new away strategy, i.e. a strategy of another module.
+///
+pub fn new_away_strategy(
+    id: &str,
+    text: &str,
+    module: &str,
+    module_url: Option<String>,
+    url: Option<String>,
+    classes: Option<Vec<String>>,
+) -> Rc<RefCell<AwayNode>> {
+    let mut new_classes: Vec<String> = vec!["gsnelem".to_owned(), "gsnawaystgy".to_owned()];
+    if let Some(classes) = classes {
+        classes.into_iter().for_each(|c| new_classes.push(c));
+    }
+    Rc::new(RefCell::new(AwayNode::new(
+        id,
+        text,
+        module,
+        module_url,
+        AwayType::Strategy,
+        url,
+        Some(new_classes),
+    )))
+}
+
 ///
 ///
 ///
@@ -368,6 +571,54 @@ pub fn new_away_context(
     )))
 }
 
+///
+/// Create a new counter goal i.e., a defeater of the dialectic extension.
+///
+pub fn new_counter_goal(
+    id: &str,
+    text: &str,
+    undeveloped: bool,
+    url: Option<String>,
+    classes: Option<Vec<String>>,
+) -> Rc<RefCell<BoxNode>> {
+    let mut new_classes: Vec<String> = vec!["gsnelem".to_owned(), "gsncountergoal".to_owned()];
+    if let Some(classes) = classes {
+        classes.into_iter().for_each(|c| new_classes.push(c));
+    }
+    Rc::new(RefCell::new(BoxNode::new(
+        id,
+        text,
+        undeveloped,
+        0,
+        false,
+        url,
+        Some(new_classes),
+    )))
+}
+
+///
+/// Create a new counter solution of the dialectic extension.
+///
+pub fn new_counter_solution(
+    id: &str,
+    text: &str,
+    url: Option<String>,
+    classes: Option<Vec<String>>,
+) -> Rc<RefCell<EllipticalNode>> {
+    let mut new_classes: Vec<String> = vec!["gsnelem".to_owned(), "gsncountersltn".to_owned()];
+    if let Some(classes) = classes {
+        classes.into_iter().for_each(|c| new_classes.push(c));
+    }
+    Rc::new(RefCell::new(EllipticalNode::new(
+        id,
+        text,
+        None,
+        true,
+        url,
+        Some(new_classes),
+    )))
+}
+
 ///
 ///
 ///
@@ -1,4 +1,4 @@
-#[derive(PartialEq)]
+#[derive(Clone, Copy, PartialEq)]
 pub struct Point2D {
     pub x: i32,
     pub y: i32,
@@ -0,0 +1,749 @@
+use crate::diagnostics::Diagnostics;
+use crate::gsn::{
+    get_display_id, get_local_id, get_node_type, GsnNode, GsnNodeType, IN_CONTEXT_OF, SUPPORTED_BY,
+};
+use crate::yaml_fix::MyMap;
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Display;
+use yaml_rust::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust::scanner::Marker;
+
+const UNDEVELOPED: &str = "undeveloped";
+
+///
+/// A mechanical fix of a diagnostic
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FixKind {
+    /// V05: Remove the duplicate entry at `index` of `attribute`
+    RemoveDuplicate {
+        attribute: &'static str,
+        index: usize,
+        reference: String,
+    },
+    /// V04: Move the entry at `index` of `supportedBy` to `inContextOf`
+    MoveToContext { index: usize, reference: String },
+    /// V02: Mark the element as undeveloped
+    SetUndeveloped,
+}
+
+///
+/// A fix of an element in an input file
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fix {
+    pub(crate) file: String,
+    pub(crate) element: String,
+    pub(crate) kind: FixKind,
+}
+
+impl Display for Fix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            FixKind::RemoveDuplicate {
+                attribute,
+                reference,
+                ..
+            } => write!(
+                f,
+                "V05: Removed duplicate entry {} from {} of {}.",
+                reference, attribute, self.element
+            ),
+            FixKind::MoveToContext { reference, .. } => write!(
+                f,
+                "V04: Moved {} from {} to {} of {}.",
+                reference, SUPPORTED_BY, IN_CONTEXT_OF, self.element
+            ),
+            FixKind::SetUndeveloped => {
+                write!(f, "V02: Marked {} as {}.", self.element, UNDEVELOPED)
+            }
+        }
+    }
+}
+
+///
+/// Get the fixes for all reported diagnostics that have an unambiguous fix.
+///
+/// Only diagnostics that are actually reported are fixed,
+/// i.e., suppressed or allowed warnings are left alone.
+///
+pub fn get_fixes(nodes: &MyMap<String, GsnNode>, diags: &Diagnostics) -> Vec<Fix> {
+    let is_reported = |code: &str, id: &str| {
+        diags
+            .messages
+            .iter()
+            .any(|m| m.split_code().0 == Some(code) && m.element.as_deref() == Some(id))
+    };
+    let mut fixes = Vec::new();
+    for (id, node) in nodes.iter() {
+        let file = match &node.location {
+            Some(location) => location.file.to_owned(),
+            None => continue,
+        };
+        let mut add = |kind| {
+            fixes.push(Fix {
+                file: file.to_owned(),
+                element: get_local_id(id).to_owned(),
+                kind,
+            })
+        };
+        if is_reported("V05", id) {
+            for (attribute, refs) in [
+                (SUPPORTED_BY, &node.supported_by),
+                (IN_CONTEXT_OF, &node.in_context_of),
+            ] {
+                let mut seen = BTreeSet::new();
+                for (index, reference) in refs.iter().flatten().enumerate() {
+                    if !seen.insert(reference) {
+                        add(FixKind::RemoveDuplicate {
+                            attribute,
+                            index,
+                            reference: get_display_id(reference, &node.module).to_owned(),
+                        });
+                    }
+                }
+            }
+        }
+        if is_reported("V04", id)
+            && matches!(
+                node.get_type(id),
+                Some(GsnNodeType::Goal) | Some(GsnNodeType::Strategy) | Some(GsnNodeType::Solution)
+            )
+        {
+            for (index, reference) in node.supported_by.iter().flatten().enumerate() {
+                if matches!(
+                    get_node_type(nodes, reference),
+                    Some(GsnNodeType::Context)
+                        | Some(GsnNodeType::Assumption)
+                        | Some(GsnNodeType::Justification)
+                ) {
+                    add(FixKind::MoveToContext {
+                        index,
+                        reference: get_display_id(reference, &node.module).to_owned(),
+                    });
+                }
+            }
+        }
+        if is_reported("V02", id) {
+            add(FixKind::SetUndeveloped);
+        }
+    }
+    fixes
+}
+
+///
+/// Apply the fixes to the input files.
+///
+/// Only the affected attributes are changed, the rest of the files is left as it is.
+/// Returns the fixes that have been applied.
+///
+pub fn apply_fixes(fixes: &[Fix]) -> Result<Vec<&Fix>> {
+    let mut files = BTreeMap::<&str, Vec<&Fix>>::new();
+    for fix in fixes {
+        files.entry(&fix.file).or_default().push(fix);
+    }
+    let mut applied = Vec::new();
+    for (file, fixes) in files {
+        let input =
+            std::fs::read_to_string(file).context(format!("Failed to open file {}", file))?;
+        let (output, mut file_applied) = fix_input(&input, &fixes);
+        if !file_applied.is_empty() {
+            std::fs::write(file, output).context(format!("Failed to write file {}", file))?;
+            applied.append(&mut file_applied);
+        }
+    }
+    Ok(applied)
+}
+
+///
+/// Position of a reference list in the input
+///
+#[derive(Debug, Default)]
+struct ListSpan {
+    /// Index of the attribute name
+    key: usize,
+    /// Column of the attribute name
+    indent: usize,
+    /// Indices of the brackets of a flow sequence
+    brackets: Option<(usize, usize)>,
+    /// Index and text of each entry
+    items: Vec<(usize, String)>,
+}
+
+///
+/// Position of an element in the input
+///
+#[derive(Debug, Default)]
+struct NodeSpan {
+    /// Column of the attributes, None if the element is a flow mapping
+    indent: Option<usize>,
+    /// Index where the element ends
+    end: usize,
+    /// Index and value of `undeveloped`
+    undeveloped: Option<(usize, String)>,
+    lists: BTreeMap<String, ListSpan>,
+}
+
+#[derive(Default)]
+struct SpanReceiver {
+    chars: Vec<char>,
+    depth: usize,
+    node: Option<String>,
+    key: Option<(String, Marker)>,
+    expect_key: bool,
+    nodes: BTreeMap<String, NodeSpan>,
+}
+
+impl SpanReceiver {
+    fn current(&mut self) -> Option<&mut NodeSpan> {
+        self.node.as_ref().and_then(|n| self.nodes.get_mut(n))
+    }
+}
+
+impl MarkedEventReceiver for SpanReceiver {
+    fn on_event(&mut self, ev: Event, mark: Marker) {
+        match ev {
+            Event::MappingStart(_) => {
+                self.depth += 1;
+                self.expect_key = true;
+                if self.depth == 2 {
+                    if let Some((key, _)) = self.key.take() {
+                        // The indentation is taken from the first attribute.
+                        let flow = self.chars.get(mark.index()) == Some(&'{');
+                        self.nodes.insert(
+                            key.to_owned(),
+                            NodeSpan {
+                                indent: (!flow).then_some(usize::MAX),
+                                ..Default::default()
+                            },
+                        );
+                        self.node = Some(key);
+                    }
+                }
+            }
+            Event::MappingEnd => {
+                if self.depth == 2 {
+                    if let Some(node) = self.current() {
+                        node.end = mark.index();
+                    }
+                    self.node = None;
+                }
+                self.depth -= 1;
+                self.expect_key = self.depth <= 2;
+            }
+            Event::SequenceStart(_) => {
+                self.depth += 1;
+                if let (3, Some((key, key_mark))) = (self.depth, self.key.to_owned()) {
+                    let bracket =
+                        (self.chars.get(mark.index()) == Some(&'[')).then_some(mark.index());
+                    if let Some(node) = self.current() {
+                        node.lists.insert(
+                            key,
+                            ListSpan {
+                                key: key_mark.index(),
+                                indent: key_mark.col(),
+                                brackets: bracket.map(|b| (b, b)),
+                                items: Vec::new(),
+                            },
+                        );
+                    }
+                }
+            }
+            Event::SequenceEnd => {
+                if let (3, Some((key, _))) = (self.depth, self.key.to_owned()) {
+                    let is_bracket = self.chars.get(mark.index()) == Some(&']');
+                    if let Some(list) = self.current().and_then(|n| n.lists.get_mut(&key)) {
+                        match (&mut list.brackets, is_bracket) {
+                            (Some((_, end)), true) => *end = mark.index(),
+                            // Unknown end of flow sequence, better do not touch it.
+                            (Some(_), false) => list.items.clear(),
+                            _ => (),
+                        }
+                    }
+                }
+                self.depth -= 1;
+                self.expect_key = self.depth <= 2;
+            }
+            Event::Scalar(value, ..) => match self.depth {
+                1 | 2 if self.expect_key => {
+                    if self.depth == 2 {
+                        if let Some(NodeSpan {
+                            indent: Some(indent),
+                            ..
+                        }) = self.current()
+                        {
+                            *indent = (*indent).min(mark.col());
+                        }
+                    }
+                    self.key = Some((value, mark));
+                    self.expect_key = false;
+                }
+                2 => {
+                    if let Some((key, _)) = self.key.to_owned() {
+                        if key == UNDEVELOPED {
+                            if let Some(node) = self.current() {
+                                node.undeveloped = Some((mark.index(), value));
+                            }
+                        }
+                    }
+                    self.expect_key = true;
+                }
+                3 => {
+                    // Only plain scalars can be changed safely.
+                    let plain = value
+                        .chars()
+                        .zip(self.chars.iter().skip(mark.index()))
+                        .all(|(a, b)| a == *b);
+                    if let Some((key, _)) = self.key.to_owned() {
+                        if let Some(list) = self.current().and_then(|n| n.lists.get_mut(&key)) {
+                            list.items
+                                .push((if plain { mark.index() } else { usize::MAX }, value));
+                        }
+                    }
+                }
+                _ => self.expect_key = self.depth <= 2,
+            },
+            _ => (),
+        }
+    }
+}
+
+///
+/// A replacement of the characters from `start` to `end` by `text`
+///
+struct Edit {
+    start: usize,
+    end: usize,
+    text: String,
+}
+
+///
+/// Get the index of the first character of the line that contains `index`.
+///
+fn line_start(chars: &[char], index: usize) -> usize {
+    chars[..index]
+        .iter()
+        .rposition(|c| *c == '\n')
+        .map(|p| p + 1)
+        .unwrap_or(0)
+}
+
+///
+/// Get the index after the line break of the line that contains `index`.
+///
+fn line_end(chars: &[char], index: usize) -> usize {
+    chars[index..]
+        .iter()
+        .position(|c| *c == '\n')
+        .map(|p| index + p + 1)
+        .unwrap_or(chars.len())
+}
+
+///
+/// Apply the fixes to the content of a single file.
+/// Returns the new content and the applied fixes.
+///
+fn fix_input<'a>(input: &str, fixes: &[&'a Fix]) -> (String, Vec<&'a Fix>) {
+    let chars: Vec<char> = input.chars().collect();
+    let mut receiver = SpanReceiver {
+        chars: chars.to_owned(),
+        ..Default::default()
+    };
+    let mut parser = Parser::new(input.chars());
+    if parser.load(&mut receiver, false).is_err() {
+        return (input.to_owned(), vec![]);
+    }
+    let mut by_element = BTreeMap::<&str, Vec<&Fix>>::new();
+    for fix in fixes {
+        by_element.entry(&fix.element).or_default().push(fix);
+    }
+    let mut edits = Vec::new();
+    let mut applied = Vec::new();
+    for (element, fixes) in by_element {
+        if let Some(node) = receiver.nodes.get(element) {
+            fix_node(&chars, node, &fixes, &mut edits, &mut applied);
+        }
+    }
+    // Apply from the end, such that the indices stay valid.
+    edits.sort_by_key(|e| std::cmp::Reverse((e.start, e.end)));
+    let mut output = chars;
+    for edit in edits {
+        output.splice(edit.start..edit.end, edit.text.chars());
+    }
+    (output.into_iter().collect(), applied)
+}
+
+///
+/// Create the edits for the fixes of a single element.
+///
+fn fix_node<'a>(
+    chars: &[char],
+    node: &NodeSpan,
+    fixes: &[&'a Fix],
+    edits: &mut Vec<Edit>,
+    applied: &mut Vec<&'a Fix>,
+) {
+    // Changes to a list are collected first, since they result in a single edit.
+    let mut removed = BTreeMap::<&str, BTreeSet<usize>>::new();
+    let mut moved = Vec::new();
+    let item = |attribute: &str, index: usize| {
+        node.lists
+            .get(attribute)
+            .and_then(|l| l.items.get(index))
+            .filter(|(start, _)| *start != usize::MAX)
+    };
+    let in_context: BTreeSet<&str> = node
+        .lists
+        .get(IN_CONTEXT_OF)
+        .iter()
+        .flat_map(|l| l.items.iter())
+        .map(|(_, text)| text.as_str())
+        .collect();
+    for fix in fixes {
+        match &fix.kind {
+            FixKind::RemoveDuplicate {
+                attribute, index, ..
+            } if item(attribute, *index).is_some() => {
+                removed.entry(attribute).or_default().insert(*index);
+                applied.push(*fix);
+            }
+            FixKind::MoveToContext { index, .. } if item(SUPPORTED_BY, *index).is_some() => {
+                let reference = &item(SUPPORTED_BY, *index).unwrap().1;
+                // A reference that is already a context is only removed from `supportedBy`.
+                if removed.entry(SUPPORTED_BY).or_default().insert(*index)
+                    && !in_context.contains(reference.as_str())
+                    && !moved.contains(reference)
+                {
+                    moved.push(reference.to_owned());
+                }
+                applied.push(*fix);
+            }
+            FixKind::SetUndeveloped => match (&node.undeveloped, node.indent) {
+                (Some((start, value)), _) => {
+                    edits.push(Edit {
+                        start: *start,
+                        end: start + value.chars().count(),
+                        text: "true".to_owned(),
+                    });
+                    applied.push(*fix);
+                }
+                (None, Some(indent)) => {
+                    // Insert after the last line of the element that is not empty or a comment.
+                    let mut end = node.end;
+                    while end > 0 {
+                        let start = line_start(chars, end - 1);
+                        let line: String = chars[start..end].iter().collect();
+                        if !(line.trim().is_empty() || line.trim().starts_with('#')) {
+                            break;
+                        }
+                        end = start;
+                    }
+                    let newline = if end > 0 && chars[end - 1] != '\n' {
+                        "\n"
+                    } else {
+                        ""
+                    };
+                    edits.push(Edit {
+                        start: end,
+                        end,
+                        text: format!("{}{}{}: true\n", newline, " ".repeat(indent), UNDEVELOPED),
+                    });
+                    applied.push(*fix);
+                }
+                (None, None) => (),
+            },
+            _ => (),
+        }
+    }
+    for (attribute, indices) in &removed {
+        let list = &node.lists[*attribute];
+        let added: &[String] = if *attribute == IN_CONTEXT_OF {
+            &moved
+        } else {
+            &[]
+        };
+        let remove_all = added.is_empty() && indices.len() == list.items.len();
+        edits.append(&mut rewrite_list(chars, list, indices, added, remove_all));
+    }
+    if !moved.is_empty() && !removed.contains_key(IN_CONTEXT_OF) {
+        match node.lists.get(IN_CONTEXT_OF) {
+            Some(list) => edits.append(&mut rewrite_list(
+                chars,
+                list,
+                &BTreeSet::new(),
+                &moved,
+                false,
+            )),
+            None => {
+                // Add the attribute after `supportedBy`.
+                let list = &node.lists[SUPPORTED_BY];
+                let last = list
+                    .brackets
+                    .map(|(_, end)| end)
+                    .or(list.items.last().map(|(start, _)| *start))
+                    .unwrap_or(list.key);
+                let end = line_end(chars, last);
+                let newline = if end > 0 && chars[end - 1] != '\n' {
+                    "\n"
+                } else {
+                    ""
+                };
+                edits.push(Edit {
+                    start: end,
+                    end,
+                    text: format!(
+                        "{}{}{}: [{}]\n",
+                        newline,
+                        " ".repeat(list.indent),
+                        IN_CONTEXT_OF,
+                        moved.join(", ")
+                    ),
+                });
+            }
+        }
+    }
+}
+
+///
+/// Create the edits that remove the entries at `removed` from a list
+/// and add the entries `added` at its end.
+/// If `remove_all` is set, the complete attribute is removed.
+///
+fn rewrite_list(
+    chars: &[char],
+    list: &ListSpan,
+    removed: &BTreeSet<usize>,
+    added: &[String],
+    remove_all: bool,
+) -> Vec<Edit> {
+    let last = list
+        .brackets
+        .map(|(_, end)| end)
+        .or(list.items.last().map(|(start, _)| *start))
+        .unwrap_or(list.key);
+    if remove_all {
+        return vec![Edit {
+            start: line_start(chars, list.key),
+            end: line_end(chars, last),
+            text: String::new(),
+        }];
+    }
+    match list.brackets {
+        Some((start, end)) => {
+            let items: Vec<&str> = list
+                .items
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !removed.contains(i))
+                .map(|(_, (_, text))| text.as_str())
+                .chain(added.iter().map(|a| a.as_str()))
+                .collect();
+            vec![Edit {
+                start,
+                end: end + 1,
+                text: format!("[{}]", items.join(", ")),
+            }]
+        }
+        None => {
+            let mut edits: Vec<Edit> = removed
+                .iter()
+                .map(|i| {
+                    let (start, _) = list.items[*i];
+                    Edit {
+                        start: line_start(chars, start),
+                        end: line_end(chars, start),
+                        text: String::new(),
+                    }
+                })
+                .collect();
+            if let (false, Some((start, _))) = (added.is_empty(), list.items.last()) {
+                // Use the same indentation as the last entry.
+                let prefix: String = chars[line_start(chars, *start)..*start].iter().collect();
+                let end = line_end(chars, *start);
+                let newline = if end > 0 && chars[end - 1] != '\n' {
+                    "\n"
+                } else {
+                    ""
+                };
+                edits.push(Edit {
+                    start: end,
+                    end,
+                    text: newline.to_owned()
+                        + &added
+                            .iter()
+                            .map(|a| format!("{}{}\n", prefix, a))
+                            .collect::<String>(),
+                });
+            }
+            edits
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn fix(element: &str, kind: FixKind) -> Fix {
+        Fix {
+            file: "test.gsn.yaml".to_owned(),
+            element: element.to_owned(),
+            kind,
+        }
+    }
+
+    fn apply(input: &str, fixes: &[Fix]) -> (String, usize) {
+        let fixes: Vec<&Fix> = fixes.iter().collect();
+        let (output, applied) = fix_input(input, &fixes);
+        (output, applied.len())
+    }
+
+    #[test]
+    fn remove_duplicate_from_flow_list() {
+        let input =
+            "G1:\n  text: Goal\n  supportedBy: [S1, G2, S1] # comment\n\nS1:\n  text: Solution\n";
+        let (output, applied) = apply(
+            input,
+            &[fix(
+                "G1",
+                FixKind::RemoveDuplicate {
+                    attribute: SUPPORTED_BY,
+                    index: 2,
+                    reference: "S1".to_owned(),
+                },
+            )],
+        );
+        assert_eq!(applied, 1);
+        assert_eq!(
+            output,
+            "G1:\n  text: Goal\n  supportedBy: [S1, G2] # comment\n\nS1:\n  text: Solution\n"
+        );
+    }
+
+    #[test]
+    fn remove_duplicate_from_block_list() {
+        let input =
+            "G1:\n  text: Goal\n  inContextOf:\n    - C1\n    - C1\n  supportedBy:\n  - Sn1\n";
+        let (output, applied) = apply(
+            input,
+            &[fix(
+                "G1",
+                FixKind::RemoveDuplicate {
+                    attribute: IN_CONTEXT_OF,
+                    index: 1,
+                    reference: "C1".to_owned(),
+                },
+            )],
+        );
+        assert_eq!(applied, 1);
+        assert_eq!(
+            output,
+            "G1:\n  text: Goal\n  inContextOf:\n    - C1\n  supportedBy:\n  - Sn1\n"
+        );
+    }
+
+    #[test]
+    fn set_undeveloped() {
+        let input = "G1:\n  text: Goal\n  # comment\n\nG2:\n  text: Goal\n  undeveloped: false\n\nG3: {text: Goal}\n";
+        let (output, applied) = apply(
+            input,
+            &[
+                fix("G1", FixKind::SetUndeveloped),
+                fix("G2", FixKind::SetUndeveloped),
+                fix("G3", FixKind::SetUndeveloped),
+            ],
+        );
+        // Flow mappings are left alone.
+        assert_eq!(applied, 2);
+        assert_eq!(
+            output,
+            "G1:\n  text: Goal\n  undeveloped: true\n  # comment\n\nG2:\n  text: Goal\n  undeveloped: true\n\nG3: {text: Goal}\n"
+        );
+    }
+
+    #[test]
+    fn set_undeveloped_at_end_of_file() {
+        let (output, applied) = apply("G1:\n  text: Goal", &[fix("G1", FixKind::SetUndeveloped)]);
+        assert_eq!(applied, 1);
+        assert_eq!(output, "G1:\n  text: Goal\n  undeveloped: true\n");
+    }
+
+    #[test]
+    fn move_to_new_context() {
+        let input = "G1:\n  text: Goal\n  supportedBy: [C1, S1]\n  level: top\n";
+        let (output, applied) = apply(
+            input,
+            &[fix(
+                "G1",
+                FixKind::MoveToContext {
+                    index: 0,
+                    reference: "C1".to_owned(),
+                },
+            )],
+        );
+        assert_eq!(applied, 1);
+        assert_eq!(
+            output,
+            "G1:\n  text: Goal\n  supportedBy: [S1]\n  inContextOf: [C1]\n  level: top\n"
+        );
+    }
+
+    #[test]
+    fn move_to_existing_context() {
+        let input = "G1:\n  text: Goal\n  supportedBy:\n    - C1\n  inContextOf:\n    - A1\n";
+        let (output, applied) = apply(
+            input,
+            &[fix(
+                "G1",
+                FixKind::MoveToContext {
+                    index: 0,
+                    reference: "C1".to_owned(),
+                },
+            )],
+        );
+        assert_eq!(applied, 1);
+        assert_eq!(
+            output,
+            "G1:\n  text: Goal\n  inContextOf:\n    - A1\n    - C1\n"
+        );
+    }
+
+    #[test]
+    fn move_to_context_already_present() {
+        let input = "G1:\n  text: Goal\n  supportedBy: [C1, S1]\n  inContextOf: [C1]\n";
+        let (output, applied) = apply(
+            input,
+            &[fix(
+                "G1",
+                FixKind::MoveToContext {
+                    index: 0,
+                    reference: "C1".to_owned(),
+                },
+            )],
+        );
+        assert_eq!(applied, 1);
+        assert_eq!(
+            output,
+            "G1:\n  text: Goal\n  supportedBy: [S1]\n  inContextOf: [C1]\n"
+        );
+    }
+
+    #[test]
+    fn quoted_entries_are_not_changed() {
+        let input = "G1:\n  text: Goal\n  supportedBy: [\"S1\", 'S1']\n";
+        let (output, applied) = apply(
+            input,
+            &[fix(
+                "G1",
+                FixKind::RemoveDuplicate {
+                    attribute: SUPPORTED_BY,
+                    index: 1,
+                    reference: "S1".to_owned(),
+                },
+            )],
+        );
+        assert_eq!(applied, 0);
+        assert_eq!(output, input);
+    }
+}
@@ -0,0 +1,161 @@
+use super::{get_challengers, is_rebutted, GsnNode, GsnNodeType};
+use crate::yaml_fix::MyMap;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Display;
+
+///
+/// Baconian confidence of a claim
+///
+/// The defeaters of a claim are the defeaters of the claim itself
+/// and of all elements it is supported by or in context of.
+/// A defeater is eliminated if it is rebutted (see `is_rebutted`).
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BaconianScore {
+    pub(crate) eliminated: BTreeSet<String>,
+    pub(crate) residual: BTreeSet<String>,
+}
+
+impl BaconianScore {
+    ///
+    /// Get the number of all identified defeaters.
+    ///
+    pub fn total(&self) -> usize {
+        self.eliminated.len() + self.residual.len()
+    }
+}
+
+impl Display for BaconianScore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}|{}", self.eliminated.len(), self.total())
+    }
+}
+
+///
+/// Check if the argument uses defeaters at all.
+///
+pub fn has_defeaters(nodes: &MyMap<String, GsnNode>) -> bool {
+    nodes.values().any(|n| n.challenges.is_some())
+}
+
+///
+/// Calculate the Baconian confidence of all goals.
+///
+pub fn get_baconian_scores(nodes: &MyMap<String, GsnNode>) -> BTreeMap<String, BaconianScore> {
+    let challengers = get_challengers(nodes);
+    let mut defeaters = BTreeMap::new();
+    nodes
+        .iter()
+        .filter(|(id, n)| n.get_type(id) == Some(GsnNodeType::Goal))
+        .map(|(id, _)| {
+            let mut score = BaconianScore::default();
+            for defeater in collect_defeaters(
+                nodes,
+                &challengers,
+                id,
+                &mut defeaters,
+                &mut BTreeSet::new(),
+            ) {
+                if is_rebutted(&challengers, &defeater) {
+                    score.eliminated.insert(defeater);
+                } else {
+                    score.residual.insert(defeater);
+                }
+            }
+            (id.to_owned(), score)
+        })
+        .collect()
+}
+
+///
+/// Collect the defeaters of an element and all elements below along `get_edges`.
+/// Results are cached, since elements can be reached on several paths.
+///
+fn collect_defeaters(
+    nodes: &MyMap<String, GsnNode>,
+    challengers: &BTreeMap<&str, Vec<&String>>,
+    id: &str,
+    cache: &mut BTreeMap<String, BTreeSet<String>>,
+    visiting: &mut BTreeSet<String>,
+) -> BTreeSet<String> {
+    if let Some(defeaters) = cache.get(id) {
+        return defeaters.to_owned();
+    }
+    // Cycles are reported elsewhere (C04), just stop here.
+    if !visiting.insert(id.to_owned()) {
+        return BTreeSet::new();
+    }
+    let mut defeaters: BTreeSet<String> = challengers
+        .get(id)
+        .into_iter()
+        .flatten()
+        .map(|c| c.to_string())
+        .collect();
+    if let Some(node) = nodes.get(id) {
+        for (target, _) in node.get_edges() {
+            defeaters.append(&mut collect_defeaters(
+                nodes,
+                challengers,
+                &target,
+                cache,
+                visiting,
+            ));
+        }
+    }
+    visiting.remove(id);
+    cache.insert(id.to_owned(), defeaters.to_owned());
+    defeaters
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn node(node_type: &str, supported_by: &[&str], challenges: Option<&str>) -> GsnNode {
+        GsnNode {
+            node_type: Some(node_type.to_owned()),
+            supported_by: if supported_by.is_empty() {
+                None
+            } else {
+                Some(supported_by.iter().map(|s| s.to_string()).collect())
+            },
+            challenges: challenges.map(|c| c.to_owned()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn scores_propagate() {
+        let mut nodes = MyMap::<String, GsnNode>::new();
+        nodes.insert("G1".to_owned(), node("goal", &["G2", "G3"], None));
+        nodes.insert("G2".to_owned(), node("goal", &["Sn1"], None));
+        nodes.insert("G3".to_owned(), node("goal", &["Sn1"], None));
+        nodes.insert("Sn1".to_owned(), node("solution", &[], None));
+        nodes.insert("D1".to_owned(), node("counterGoal", &[], Some("G2 -> Sn1")));
+        nodes.insert("D2".to_owned(), node("counterGoal", &[], Some("Sn1")));
+        nodes.insert("D3".to_owned(), node("counterGoal", &[], Some("G3")));
+        nodes.insert("E1".to_owned(), node("counterSolution", &[], Some("D1")));
+        nodes.insert("E2".to_owned(), node("counterSolution", &[], Some("D2")));
+        // E2 is defeated itself, thus D2 is not eliminated
+        nodes.insert("D4".to_owned(), node("counterGoal", &[], Some("E2")));
+        let scores = get_baconian_scores(&nodes);
+        assert_eq!(scores.len(), 3);
+        assert_eq!(scores.get("G1").unwrap().to_string(), "1|3");
+        assert_eq!(scores.get("G2").unwrap().to_string(), "1|2");
+        assert_eq!(scores.get("G3").unwrap().to_string(), "0|2");
+        assert_eq!(
+            scores.get("G3").unwrap().residual,
+            BTreeSet::from(["D2".to_owned(), "D3".to_owned()])
+        );
+    }
+
+    #[test]
+    fn no_defeaters() {
+        let mut nodes = MyMap::<String, GsnNode>::new();
+        nodes.insert("G1".to_owned(), node("goal", &["Sn1"], None));
+        nodes.insert("Sn1".to_owned(), node("solution", &[], None));
+        assert!(!has_defeaters(&nodes));
+        let scores = get_baconian_scores(&nodes);
+        assert_eq!(scores.get("G1").unwrap().to_string(), "0|0");
+    }
+}
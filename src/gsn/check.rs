@@ -1,24 +1,41 @@
-use super::GsnNode;
+use super::confidence::{get_confidences, has_confidences};
+use super::suggest::{format_suggestions, get_suggestions};
+use super::{
+    get_display_id, get_node_type, get_single_module, GsnNode, GsnNodeType, Module, IN_CONTEXT_OF,
+    SUPPORTED_BY,
+};
 use crate::diagnostics::Diagnostics;
 use crate::yaml_fix::MyMap;
-use std::collections::{BTreeMap, BTreeSet};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs::File;
+use std::path::{Path, PathBuf};
 
 ///
 /// Entry function to all checks.
 ///
+/// All independent checks are always executed to report as many problems as possible.
+/// Returns true if the elements were checked to be free of cycles.
 ///
 pub fn check_nodes(
     diag: &mut Diagnostics,
     nodes: &MyMap<String, GsnNode>,
     excluded_modules: Option<Vec<&str>>,
-) {
-    check_node_references(diag, nodes, excluded_modules);
-    check_root_nodes(diag, nodes)
-        .map(|_| {
-            check_levels(diag, nodes);
-            check_cycles(diag, nodes);
-        })
-        .unwrap_or(());
+) -> bool {
+    let ex_mods = excluded_modules.unwrap_or_default();
+    check_node_references(diag, nodes, &ex_mods);
+    check_module_interfaces(diag, nodes, &ex_mods);
+    check_defeaters(diag, nodes, &ex_mods);
+    check_levels(diag, nodes);
+    let root_nodes = check_root_nodes(diag, nodes);
+    let acyclic = check_cycles(diag, nodes);
+    // Reachability can only be checked starting from root elements.
+    if let Ok(root_nodes) = root_nodes {
+        if !root_nodes.is_empty() {
+            check_reachability(diag, nodes, &root_nodes);
+        }
+    }
+    acyclic
 }
 
 ///
@@ -39,18 +56,20 @@ fn check_root_nodes(
                 None,
                 format!(
                     "C01: There is more than one unreferenced element: {}.",
-                    wn.join(", ")
+                    get_display_ids(nodes, &wn).join(", ")
                 ),
             );
         }
         x if x == 1 => {
             let rootn = root_nodes.get(0).unwrap();
-            if !rootn.starts_with('G') {
-                diag.add_error(
+            if get_node_type(nodes, rootn) != Some(GsnNodeType::Goal) {
+                diag.add_error_at(
                     None,
+                    Some(rootn),
+                    nodes.get(rootn).and_then(|n| n.location.as_ref()),
                     format!(
                         "C02: The root element should be a goal, but {} was found.",
-                        rootn
+                        get_display_id(rootn, get_single_module(nodes))
                     ),
                 );
             }
@@ -65,142 +84,380 @@ fn check_root_nodes(
             // Ignore empty document.
         }
     }
-    if diag.errors == 0 {
-        Ok(root_nodes)
-    } else {
+    if root_nodes.is_empty() && !nodes.is_empty() {
         Err(())
+    } else {
+        Ok(root_nodes)
     }
 }
 
+///
+/// Get the identifiers of elements as shown in messages that are not specific to a module.
+/// Module names are omitted if all elements belong to the same module.
+///
+fn get_display_ids<'a>(nodes: &MyMap<String, GsnNode>, ids: &'a [String]) -> Vec<&'a str> {
+    let module = get_single_module(nodes);
+    ids.iter().map(|id| get_display_id(id, module)).collect()
+}
+
 ///
 /// Check references of a node
 ///
 ///
-fn check_node_references(
-    diag: &mut Diagnostics,
-    nodes: &MyMap<String, GsnNode>,
-    excluded_modules: Option<Vec<&str>>,
-) {
-    let ex_mods = excluded_modules.iter().flatten().collect::<Vec<_>>();
+fn check_node_references(diag: &mut Diagnostics, nodes: &MyMap<String, GsnNode>, ex_mods: &[&str]) {
     for (id, node) in nodes
         .iter()
-        .filter(|(_, n)| !ex_mods.contains(&&n.module.as_str()))
+        .filter(|(_, n)| !ex_mods.contains(&n.module.as_str()))
     {
-        if let Some(context) = node.in_context_of.as_ref() {
-            context
+        // Elements of all modules except the element itself are candidates for suggestions.
+        let suggest = |wref: &str| {
+            format_suggestions(&get_suggestions(
+                wref,
+                nodes
+                    .keys()
+                    .filter(|k| *k != id)
+                    .map(|k| get_display_id(k, &node.module)),
+            ))
+        };
+        for (attribute, diag_str) in [
+            (IN_CONTEXT_OF, "context"),
+            (SUPPORTED_BY, "supported by element"),
+        ] {
+            for (index, wref) in node
+                .get_references(attribute)
                 .iter()
+                .enumerate()
+                .filter(|(_, n)| !nodes.contains_key(*n))
+            {
+                diag.add_reference_error_at(
+                    Some(&node.module),
+                    Some(id),
+                    wref,
+                    node.get_reference_location_at(attribute, index),
+                    format!(
+                        "C03: Element {} has unresolved {}: {}{}",
+                        get_display_id(id, &node.module),
+                        diag_str,
+                        wref,
+                        suggest(wref)
+                    ),
+                );
+            }
+        }
+        if let Some((challenged, link_target)) = node.get_challenged() {
+            std::iter::once(challenged)
+                .chain(link_target)
                 .filter(|&n| !nodes.contains_key(n))
                 .for_each(|wref| {
-                    diag.add_error(
+                    diag.add_reference_error_at(
                         Some(&node.module),
-                        format!("C03: Element {} has unresolved {}: {}", id, "context", wref),
+                        Some(id),
+                        wref,
+                        node.location.as_ref(),
+                        format!(
+                            "C03: Element {} has unresolved {}: {}{}",
+                            get_display_id(id, &node.module),
+                            "challenged element",
+                            wref,
+                            suggest(wref)
+                        ),
                     );
                 });
         }
-        if let Some(support) = node.supported_by.as_ref() {
-            support
-                .iter()
-                .filter(|&n| !nodes.contains_key(n))
-                .for_each(|wref| {
-                    diag.add_error(
+    }
+}
+
+///
+/// Check for defeaters that are neither rebutted nor supported.
+///
+fn check_defeaters(diag: &mut Diagnostics, nodes: &MyMap<String, GsnNode>, ex_mods: &[&str]) {
+    for id in super::get_unresolved_defeaters(nodes) {
+        let node = nodes.get(id).unwrap();
+        if !ex_mods.contains(&node.module.as_str()) {
+            diag.add_warning_at(
+                Some(&node.module),
+                Some(id),
+                node.location.as_ref(),
+                format!(
+                    "C10: Defeater {} is unresolved.",
+                    get_display_id(id, &node.module)
+                ),
+            );
+        }
+    }
+}
+
+///
+/// Check that elements of other modules are only referenced if they are public.
+/// A module without any public element has no explicit interface,
+/// thus, all of its elements can be referenced.
+///
+fn check_module_interfaces(
+    diag: &mut Diagnostics,
+    nodes: &MyMap<String, GsnNode>,
+    ex_mods: &[&str],
+) {
+    let interface_modules: BTreeSet<&str> = nodes
+        .values()
+        .filter(|n| n.public == Some(true))
+        .map(|n| n.module.as_str())
+        .collect();
+    for (id, node) in nodes
+        .iter()
+        .filter(|(_, n)| !ex_mods.contains(&n.module.as_str()))
+    {
+        for (target_id, _) in node.get_edges() {
+            if let Some(target) = nodes.get(&target_id) {
+                if target.module != node.module
+                    && interface_modules.contains(target.module.as_str())
+                    && target.public != Some(true)
+                {
+                    diag.add_reference_error_at(
                         Some(&node.module),
+                        Some(id),
+                        &target_id,
+                        node.get_reference_location(&target_id),
                         format!(
-                            "C03: Element {} has unresolved {}: {}",
-                            id, "supported by element", wref
+                            "C09: Element {} references private element {} of module {}.",
+                            get_display_id(id, &node.module),
+                            get_display_id(&target_id, &node.module),
+                            target.module
                         ),
                     );
-                });
+                }
+            }
         }
     }
 }
 
 ///
-/// Check for cycles in `supported by` references
-/// It also detects if there is a cycle in an independent graph.
+/// Get the cycles in `supportedBy` and `inContextOf` references.
 ///
+/// The strongly connected components are determined with Tarjan's algorithm in linear time.
+/// Every strongly connected component with a cycle is returned once with
+/// the first cycle found in it and all elements of the component.
+/// A cycle is given as the path from the referenced element to the referencing element
+/// of the reference that closes it on the current path of the depth-first search.
+/// The search starts at `start` and continues with all elements that have not been visited yet.
+/// Unresolved references are ignored.
 ///
-fn check_cycles(diag: &mut Diagnostics, nodes: &MyMap<String, GsnNode>) {
-    let mut visited: BTreeSet<String> = BTreeSet::new();
-    let root_nodes = super::get_root_nodes(nodes);
-    let cloned_root_nodes = root_nodes.to_vec();
+fn get_cycles<'a>(
+    nodes: &'a MyMap<String, GsnNode>,
+    start: &[String],
+) -> Vec<(Vec<&'a str>, Vec<&'a str>)> {
+    let ids: Vec<&str> = nodes.keys().map(|id| id.as_str()).collect();
+    let indices: BTreeMap<&str, usize> = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+    let edges: Vec<Vec<usize>> = nodes
+        .values()
+        .map(|node| {
+            node.supported_by
+                .iter()
+                .flatten()
+                .chain(node.in_context_of.iter().flatten())
+                .filter_map(|r| indices.get(r.as_str()).copied())
+                .collect()
+        })
+        .collect();
+    let mut index: Vec<Option<usize>> = vec![None; ids.len()];
+    let mut lowlink = vec![0; ids.len()];
+    let mut on_stack = vec![false; ids.len()];
+    // Position of an element on the current path of the depth-first search
+    let mut on_path: Vec<Option<usize>> = vec![None; ids.len()];
     let mut stack = Vec::new();
-    let mut ancestors = Vec::new();
-
-    for root in &root_nodes {
-        visited.insert(root.to_owned());
-        stack.push((root.to_owned(), 0));
-    }
-    let mut depth = 0;
-    while let Some((p_id, rdepth)) = stack.pop() {
-        // Jump back to current ancestor
-        if rdepth < depth {
-            // It is not sufficient to pop here, since one could skip levels when cleaning up.
-            ancestors.resize(rdepth, "".to_owned());
-            depth = rdepth;
+    let mut path: Vec<(usize, usize)> = Vec::new();
+    let mut next_index = 0;
+    let mut cycles: Vec<Vec<usize>> = Vec::new();
+    // Strongly connected component of each element and the elements of each component
+    let mut component: Vec<usize> = vec![0; ids.len()];
+    let mut components: Vec<Vec<&str>> = Vec::new();
+    let order = start
+        .iter()
+        .filter_map(|id| indices.get(id.as_str()).copied())
+        .chain(0..ids.len());
+    for root in order {
+        if index[root].is_some() {
+            continue;
         }
-        // Increase depth if current node has children that are not Solutions
-        if nodes
-            .get(&p_id)
-            .unwrap()
-            .supported_by
+        index[root] = Some(next_index);
+        lowlink[root] = next_index;
+        next_index += 1;
+        stack.push(root);
+        on_stack[root] = true;
+        on_path[root] = Some(path.len());
+        path.push((root, 0));
+        while let Some((v, next_edge)) = path.last_mut() {
+            let v = *v;
+            if let Some(w) = edges[v].get(*next_edge).copied() {
+                *next_edge += 1;
+                match index[w] {
+                    None => {
+                        index[w] = Some(next_index);
+                        lowlink[w] = next_index;
+                        next_index += 1;
+                        stack.push(w);
+                        on_stack[w] = true;
+                        on_path[w] = Some(path.len());
+                        path.push((w, 0));
+                    }
+                    Some(w_index) if on_stack[w] => {
+                        lowlink[v] = lowlink[v].min(w_index);
+                        if let Some(pos) = on_path[w] {
+                            cycles.push(path[pos..].iter().map(|(x, _)| *x).collect());
+                        }
+                    }
+                    Some(_) => (),
+                }
+            } else {
+                path.pop();
+                on_path[v] = None;
+                if let Some((u, _)) = path.last() {
+                    lowlink[*u] = lowlink[*u].min(lowlink[v]);
+                }
+                if Some(lowlink[v]) == index[v] {
+                    // Pop the strongly connected component of v
+                    let mut members = Vec::new();
+                    while let Some(w) = stack.pop() {
+                        on_stack[w] = false;
+                        component[w] = components.len();
+                        members.push(ids[w]);
+                        if w == v {
+                            break;
+                        }
+                    }
+                    members.sort();
+                    components.push(members);
+                }
+            }
+        }
+    }
+    // All elements of a cycle are in the same component, only the first cycle per component is kept.
+    let mut reported = BTreeSet::new();
+    cycles
+        .into_iter()
+        .filter(|cycle| reported.insert(component[cycle[0]]))
+        .map(|cycle| {
+            (
+                cycle.iter().map(|x| ids[*x]).collect(),
+                components[component[cycle[0]]].to_owned(),
+            )
+        })
+        .collect()
+}
+
+///
+/// Check for cycles in `supportedBy` and `inContextOf` references.
+/// Each group of elements that are in cycles with each other is reported once
+/// with the complete path of one cycle, also in independent graphs.
+/// Returns true if no cycle was found.
+///
+fn check_cycles(diag: &mut Diagnostics, nodes: &MyMap<String, GsnNode>) -> bool {
+    let cycles = get_cycles(nodes, &super::get_root_nodes(nodes));
+    let module = get_single_module(nodes);
+    let display = |id| get_display_id(id, module);
+    for (cycle, members) in &cycles {
+        let first = cycle[0];
+        let last = cycle[cycle.len() - 1];
+        let on_cycle: BTreeSet<&str> = cycle.iter().copied().collect();
+        let others: Vec<&str> = members
             .iter()
-            .flatten()
-            .filter(|x| !x.starts_with("Sn"))
-            .count()
-            > 0
-        {
-            depth += 1;
-            ancestors.push(p_id.to_owned());
+            .filter(|m| !on_cycle.contains(*m))
+            .map(|m| display(m))
+            .collect();
+        diag.add_error_at(
+            None,
+            Some(last),
+            nodes.get(last).unwrap().get_reference_location(first),
+            format!(
+                "C04: Cycle detected at element {}. Cycle is {} -> {}.{}",
+                display(last),
+                cycle
+                    .iter()
+                    .map(|id| display(id))
+                    .collect::<Vec<_>>()
+                    .join(" -> "),
+                display(first),
+                if others.is_empty() {
+                    "".to_owned()
+                } else {
+                    format!(
+                        " Further elements in cycles with it: {}.",
+                        others.join(", ")
+                    )
+                }
+            ),
+        );
+    }
+    cycles.is_empty()
+}
+
+///
+/// Check if all elements are reachable from the root elements.
+/// Elements that challenge a reachable element are reachable, too.
+///
+fn check_reachability(diag: &mut Diagnostics, nodes: &MyMap<String, GsnNode>, roots: &[String]) {
+    // Challengers are attached to the challenged element.
+    let mut challengers = BTreeMap::<&str, Vec<&str>>::new();
+    for (id, node) in nodes.iter() {
+        if let Some((challenged, _)) = node.get_challenged() {
+            challengers.entry(challenged).or_default().push(id);
         }
-        // Remember the incontext elements for the reachability analysis below.
-        nodes
-            .get(&p_id)
-            .unwrap()
-            .in_context_of
+    }
+    let mut visited: BTreeSet<&str> = roots.iter().map(|r| r.as_str()).collect();
+    let mut stack: Vec<&str> = visited.iter().copied().collect();
+    while let Some(id) = stack.pop() {
+        let node = nodes.get(id).unwrap();
+        for child in node
+            .supported_by
             .iter()
             .flatten()
-            .for_each(|x| {
-                visited.insert(x.to_owned());
-            });
-        for child_node in nodes.get(&p_id).unwrap().supported_by.iter().flatten() {
-            // Remember the solutions for reachability analyis.
-            visited.insert(child_node.to_owned());
-            if !child_node.starts_with("Sn") {
-                if ancestors.contains(child_node) {
-                    diag.add_error(
-                        None,
-                        format!(
-                            "C04: Cycle detected at element {}. Cycle is {} -> {} -> {}.",
-                            &p_id,
-                            child_node,
-                            &ancestors
-                                .rsplit(|x| x == child_node)
-                                .next()
-                                .unwrap()
-                                .join(" -> "),
-                            child_node
-                        ),
-                    );
-                    break;
-                }
-                stack.push((child_node.to_owned(), depth));
+            .chain(node.in_context_of.iter().flatten())
+            .filter_map(|r| nodes.get_key_value(r).map(|(k, _)| k.as_str()))
+            .chain(challengers.get(id).into_iter().flatten().copied())
+        {
+            if visited.insert(child) {
+                stack.push(child);
             }
         }
     }
-    let node_keys = BTreeSet::from_iter(nodes.keys().cloned());
-    let unvisited: BTreeSet<&String> = node_keys.difference(&visited).collect();
-
+    let unvisited: Vec<String> = nodes
+        .keys()
+        .filter(|id| !visited.contains(id.as_str()))
+        .cloned()
+        .collect();
     if !unvisited.is_empty() {
         diag.add_error(
             None,
             format!(
                 "C08: The following element(s) are not reachable from the root element(s) ({}): {}",
-                cloned_root_nodes.join(", "),
-                Vec::from_iter(unvisited)
-                    .into_iter()
-                    .cloned()
-                    .collect::<Vec<String>>()
-                    .join(", ")
+                get_display_ids(nodes, roots).join(", "),
+                get_display_ids(nodes, &unvisited).join(", ")
+            ),
+        );
+    }
+}
+
+///
+/// Check if the confidence of goals is below the given threshold.
+///
+pub fn check_confidence(diag: &mut Diagnostics, nodes: &MyMap<String, GsnNode>, threshold: f64) {
+    if !has_confidences(nodes) {
+        return;
+    }
+    for (id, opinion) in get_confidences(nodes)
+        .iter()
+        .filter(|(id, _)| get_node_type(nodes, id) == Some(GsnNodeType::Goal))
+        .filter(|(_, o)| o.belief < threshold)
+    {
+        let node = nodes.get(id).unwrap();
+        diag.add_warning_at(
+            Some(&node.module),
+            Some(id),
+            node.location.as_ref(),
+            format!(
+                "C11: Confidence {} of goal {} is below the threshold {}.",
+                opinion,
+                get_display_id(id, &node.module),
+                threshold
             ),
         );
     }
@@ -221,7 +478,211 @@ fn check_levels(diag: &mut Diagnostics, nodes: &MyMap<String, GsnNode>) {
     levels
         .iter()
         .filter(|(_, &count)| count == 1)
-        .for_each(|(l, _)| diag.add_warning(None, format!("C05: Level {} is only used once.", l)));
+        .for_each(|(l, _)| {
+            diag.add_keyed_warning(None, l, format!("C05: Level {} is only used once.", l))
+        });
+}
+
+///
+/// Check that elements are only supported by elements on lower levels.
+///
+/// The order of levels is given by the module information of the supported element.
+/// Elements without level or with an undeclared level are ignored.
+///
+pub fn check_level_order(
+    diag: &mut Diagnostics,
+    nodes: &MyMap<String, GsnNode>,
+    modules: &HashMap<String, Module>,
+) {
+    for (id, node) in nodes.iter() {
+        let levels = match modules
+            .get(&node.module)
+            .and_then(|m| m.meta.as_ref())
+            .and_then(|m| m.levels.as_ref())
+        {
+            Some(levels) => levels,
+            None => continue,
+        };
+        let rank = |n: &GsnNode| {
+            n.level
+                .as_ref()
+                .and_then(|l| levels.iter().position(|x| x.trim() == l.trim()))
+        };
+        let node_rank = match rank(node) {
+            Some(r) => r,
+            None => continue,
+        };
+        for (child_id, child) in node
+            .supported_by
+            .iter()
+            .flatten()
+            .filter_map(|c| nodes.get_key_value(c))
+        {
+            if let Some(child_rank) = rank(child).filter(|r| *r <= node_rank) {
+                diag.add_error_at(
+                    Some(&node.module),
+                    Some(id),
+                    node.get_reference_location(child_id),
+                    format!(
+                        "C12: Element {} on level {} is supported by element {} on level {}, which is {}.",
+                        get_display_id(id, &node.module),
+                        levels[node_rank],
+                        get_display_id(child_id, &node.module),
+                        levels[child_rank],
+                        if child_rank == node_rank {
+                            "the same level"
+                        } else {
+                            "above"
+                        }
+                    ),
+                );
+            }
+        }
+    }
+}
+
+///
+/// Get the path of a local evidence file referenced by `url`.
+///
+/// URLs with a scheme e.g., `https://` are not local files, except for `file://` URLs on the local host.
+/// Relative paths are resolved relative to the directory of `module_file`.
+/// A fragment e.g., `#page=3` is ignored.
+///
+fn get_evidence_path(url: &str, module_file: &str) -> Option<PathBuf> {
+    let url = url.split('#').next().unwrap_or(url).trim();
+    let path = match url.get(..7) {
+        Some(scheme) if scheme.eq_ignore_ascii_case("file://") => {
+            let path = &url[7..];
+            let path = path.strip_prefix("localhost").unwrap_or(path);
+            // Other hosts are not local.
+            if !path.starts_with('/') {
+                return None;
+            }
+            // A drive on Windows is given as `file:///C:/...`.
+            let path = match path.get(2..3) {
+                Some(":") => &path[1..],
+                _ => path,
+            };
+            return Some(PathBuf::from(decode_percent(path)));
+        }
+        _ => url,
+    };
+    // A single letter is a drive on Windows, not a scheme.
+    let has_scheme = path.split_once(':').is_some_and(|(scheme, _)| {
+        scheme.len() > 1
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+    });
+    if has_scheme || path.is_empty() {
+        return None;
+    }
+    Some(
+        Path::new(module_file)
+            .parent()
+            .unwrap_or_else(|| Path::new(""))
+            .join(path),
+    )
+}
+
+///
+/// Decode percent-encoded characters of a `file://` URL e.g., `%20` for a space.
+/// Invalid encodings are kept as they are.
+///
+fn decode_percent(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match (bytes[i], path.get(i + 1..i + 3)) {
+            (b'%', Some(hex)) if u8::from_str_radix(hex, 16).is_ok() => {
+                decoded.push(u8::from_str_radix(hex, 16).unwrap());
+                i += 3;
+            }
+            (b, _) => {
+                decoded.push(b);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+///
+/// Get the SHA-256 hash of the file at `path` as lower case hex string.
+///
+fn get_sha256(path: &Path) -> std::io::Result<String> {
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+///
+/// Check that local evidence files referenced by `url` of solutions exist
+/// and that their content matches a recorded `sha256` hash.
+///
+pub fn check_evidence_files(
+    diag: &mut Diagnostics,
+    nodes: &MyMap<String, GsnNode>,
+    modules: &HashMap<String, Module>,
+    ex_mods: &[&str],
+) {
+    for (id, node) in nodes.iter().filter(|(id, n)| {
+        n.get_type(id) == Some(GsnNodeType::Solution) && !ex_mods.contains(&n.module.as_str())
+    }) {
+        let path = match (&node.url, modules.get(&node.module)) {
+            (Some(url), Some(module)) => match get_evidence_path(url, &module.filename) {
+                Some(path) => path,
+                None => continue,
+            },
+            _ => continue,
+        };
+        if !path.is_file() {
+            diag.add_error_at(
+                Some(&node.module),
+                Some(id),
+                node.location.as_ref(),
+                format!(
+                    "C13: Evidence file {} of element {} does not exist.",
+                    path.display(),
+                    get_display_id(id, &node.module)
+                ),
+            );
+            continue;
+        }
+        if let Some(expected) = &node.sha256 {
+            match get_sha256(&path) {
+                Ok(actual) if actual.eq_ignore_ascii_case(expected.trim()) => (),
+                Ok(actual) => diag.add_error_at(
+                    Some(&node.module),
+                    Some(id),
+                    node.location.as_ref(),
+                    format!(
+                        "C14: Evidence file {} of element {} has changed. Its sha256 is {}, but {} is recorded.",
+                        path.display(),
+                        get_display_id(id, &node.module),
+                        actual,
+                        expected.trim()
+                    ),
+                ),
+                Err(e) => diag.add_error_at(
+                    Some(&node.module),
+                    Some(id),
+                    node.location.as_ref(),
+                    format!(
+                        "C14: Evidence file {} of element {} cannot be read: {}.",
+                        path.display(),
+                        get_display_id(id, &node.module),
+                        e
+                    ),
+                ),
+            }
+        }
+    }
 }
 
 ///
@@ -232,12 +693,24 @@ fn check_levels(diag: &mut Diagnostics, nodes: &MyMap<String, GsnNode>) {
 pub fn check_layers(diag: &mut Diagnostics, nodes: &MyMap<String, GsnNode>, layers: &[&str]) {
     let reserved_words = [
         "text",
+        "type",
         "inContextOf",
         "supportedBy",
         "classes",
         "url",
         "level",
         "undeveloped",
+        "public",
+        "moduleRef",
+        "uninstantiated",
+        "multiplicity",
+        "choice",
+        "acp",
+        "challenges",
+        "confidence",
+        "combination",
+        "suppress",
+        "sha256",
     ];
     for l in layers {
         if reserved_words.contains(l) {
@@ -251,8 +724,9 @@ pub fn check_layers(diag: &mut Diagnostics, nodes: &MyMap<String, GsnNode>, laye
             .iter()
             .any(|(_, n)| n.additional.contains_key(l.to_owned()))
         {
-            diag.add_warning(
+            diag.add_keyed_warning(
                 None,
+                l,
                 format!(
                     "Layer {} is not used in file. No additional output will be generated.",
                     l
@@ -266,6 +740,7 @@ pub fn check_layers(diag: &mut Diagnostics, nodes: &MyMap<String, GsnNode>, laye
 mod test {
     use super::*;
     use crate::diagnostics::DiagType;
+    use crate::gsn::get_suppressions;
 
     #[test]
     fn unresolved_ref_context() {
@@ -401,14 +876,18 @@ mod test {
             },
         );
         check_nodes(&mut d, &nodes, None);
-        assert_eq!(d.messages.len(), 1);
+        assert_eq!(d.messages.len(), 2);
         assert_eq!(d.messages[0].module, None);
         assert_eq!(d.messages[0].diag_type, DiagType::Error);
         assert_eq!(
             d.messages[0].msg,
             "C01: There are no unreferenced elements found."
         );
-        assert_eq!(d.errors, 1);
+        assert_eq!(
+            d.messages[1].msg,
+            "C04: Cycle detected at element G2. Cycle is G1 -> S1 -> G2 -> G1."
+        );
+        assert_eq!(d.errors, 2);
         assert_eq!(d.warnings, 0);
     }
 
@@ -510,17 +989,153 @@ mod test {
             },
         );
         check_nodes(&mut d, &nodes, None);
-        assert_eq!(d.messages.len(), 1);
+        assert_eq!(d.messages.len(), 2);
         assert_eq!(d.messages[0].module, None);
         assert_eq!(d.messages[0].diag_type, DiagType::Error);
         assert_eq!(
             d.messages[0].msg,
+            "C04: Cycle detected at element G2. Cycle is G1 -> S1 -> G2 -> G1."
+        );
+        assert_eq!(
+            d.messages[1].msg,
             "C08: The following element(s) are not reachable from the root element(s) (G3): G1, G2, S1"
         );
-        assert_eq!(d.errors, 1);
+        assert_eq!(d.errors, 2);
         assert_eq!(d.warnings, 0);
     }
 
+    #[test]
+    fn context_cycle() {
+        let mut d = Diagnostics::default();
+        let mut nodes = MyMap::<String, GsnNode>::new();
+        nodes.insert(
+            "G1".to_owned(),
+            GsnNode {
+                supported_by: Some(vec!["G2".to_owned()]),
+                ..Default::default()
+            },
+        );
+        nodes.insert(
+            "G2".to_owned(),
+            GsnNode {
+                undeveloped: Some(true),
+                in_context_of: Some(vec!["C1".to_owned()]),
+                ..Default::default()
+            },
+        );
+        nodes.insert(
+            "C1".to_owned(),
+            GsnNode {
+                in_context_of: Some(vec!["G2".to_owned()]),
+                ..Default::default()
+            },
+        );
+        assert!(!check_cycles(&mut d, &nodes));
+        assert_eq!(d.messages.len(), 1);
+        assert_eq!(
+            d.messages[0].msg,
+            "C04: Cycle detected at element C1. Cycle is G2 -> C1 -> G2."
+        );
+    }
+
+    #[test]
+    fn all_cycles() {
+        let mut d = Diagnostics::default();
+        let mut nodes = MyMap::<String, GsnNode>::new();
+        nodes.insert(
+            "G1".to_owned(),
+            GsnNode {
+                supported_by: Some(vec!["S1".to_owned(), "G3".to_owned()]),
+                ..Default::default()
+            },
+        );
+        nodes.insert(
+            "S1".to_owned(),
+            GsnNode {
+                supported_by: Some(vec!["G2".to_owned()]),
+                ..Default::default()
+            },
+        );
+        nodes.insert(
+            "G2".to_owned(),
+            GsnNode {
+                supported_by: Some(vec!["S1".to_owned(), "G1".to_owned()]),
+                ..Default::default()
+            },
+        );
+        nodes.insert(
+            "G3".to_owned(),
+            GsnNode {
+                supported_by: Some(vec!["G3".to_owned()]),
+                ..Default::default()
+            },
+        );
+        assert!(!check_cycles(&mut d, &nodes));
+        assert_eq!(d.messages.len(), 2);
+        assert_eq!(
+            d.messages[0].msg,
+            "C04: Cycle detected at element G2. Cycle is S1 -> G2 -> S1. Further elements in cycles with it: G1."
+        );
+        assert_eq!(
+            d.messages[1].msg,
+            "C04: Cycle detected at element G3. Cycle is G3 -> G3."
+        );
+    }
+
+    #[test]
+    fn cycle_over_cross_reference() {
+        let mut d = Diagnostics::default();
+        let mut nodes = MyMap::<String, GsnNode>::new();
+        nodes.insert(
+            "A".to_owned(),
+            GsnNode {
+                supported_by: Some(vec!["B".to_owned(), "C".to_owned()]),
+                ..Default::default()
+            },
+        );
+        nodes.insert(
+            "B".to_owned(),
+            GsnNode {
+                supported_by: Some(vec!["A".to_owned()]),
+                ..Default::default()
+            },
+        );
+        nodes.insert(
+            "C".to_owned(),
+            GsnNode {
+                supported_by: Some(vec!["B".to_owned()]),
+                ..Default::default()
+            },
+        );
+        assert!(!check_cycles(&mut d, &nodes));
+        assert_eq!(d.messages.len(), 1);
+        assert_eq!(
+            d.messages[0].msg,
+            "C04: Cycle detected at element B. Cycle is A -> B -> A. Further elements in cycles with it: C."
+        );
+    }
+
+    #[test]
+    fn long_chain() {
+        let mut d = Diagnostics::default();
+        let mut nodes = MyMap::<String, GsnNode>::new();
+        let len = 100_000;
+        for i in 0..len {
+            nodes.insert(
+                format!("G{}", i),
+                GsnNode {
+                    supported_by: Some(vec![format!("G{}", (i + 1) % len)]),
+                    ..Default::default()
+                },
+            );
+        }
+        assert!(!check_cycles(&mut d, &nodes));
+        assert_eq!(d.messages.len(), 1);
+        assert!(d.messages[0]
+            .msg
+            .starts_with("C04: Cycle detected at element G99999. Cycle is G0 -> G1 -> G2"));
+    }
+
     #[test]
     fn wrong_root() {
         let mut d = Diagnostics::default();
@@ -544,7 +1159,10 @@ mod test {
         let mut nodes = MyMap::<String, GsnNode>::new();
 
         let mut admap = MyMap::new();
-        admap.insert("layer1".to_owned(), "dontcare".to_owned());
+        admap.insert(
+            "layer1".to_owned(),
+            serde_yaml::Value::String("dontcare".to_owned()),
+        );
         nodes.insert(
             "Sn1".to_owned(),
             GsnNode {
@@ -582,7 +1200,10 @@ mod test {
         let mut nodes = MyMap::<String, GsnNode>::new();
 
         let mut admap = MyMap::new();
-        admap.insert("layer1".to_owned(), "dontcare".to_owned());
+        admap.insert(
+            "layer1".to_owned(),
+            serde_yaml::Value::String("dontcare".to_owned()),
+        );
         nodes.insert(
             "Sn1".to_owned(),
             GsnNode {
@@ -608,7 +1229,10 @@ mod test {
         let mut nodes = MyMap::<String, GsnNode>::new();
 
         let mut admap = MyMap::new();
-        admap.insert("layer1".to_owned(), "dontcare".to_owned());
+        admap.insert(
+            "layer1".to_owned(),
+            serde_yaml::Value::String("dontcare".to_owned()),
+        );
         nodes.insert(
             "Sn1".to_owned(),
             GsnNode {
@@ -662,4 +1286,361 @@ mod test {
         assert!(check_root_nodes(&mut d, &nodes).is_ok());
         assert_eq!(d.messages.len(), 0);
     }
+
+    fn interface_nodes() -> MyMap<String, GsnNode> {
+        let mut nodes = MyMap::<String, GsnNode>::new();
+        nodes.insert(
+            "Main::G1".to_owned(),
+            GsnNode {
+                supported_by: Some(vec!["Sub::G2".to_owned(), "Sub::G3".to_owned()]),
+                module: "Main".to_owned(),
+                ..Default::default()
+            },
+        );
+        nodes.insert(
+            "Sub::G2".to_owned(),
+            GsnNode {
+                undeveloped: Some(true),
+                public: Some(true),
+                module: "Sub".to_owned(),
+                ..Default::default()
+            },
+        );
+        nodes.insert(
+            "Sub::G3".to_owned(),
+            GsnNode {
+                undeveloped: Some(true),
+                module: "Sub".to_owned(),
+                ..Default::default()
+            },
+        );
+        nodes
+    }
+
+    #[test]
+    fn private_reference() {
+        let mut d = Diagnostics::default();
+        let nodes = interface_nodes();
+        check_module_interfaces(&mut d, &nodes, &[]);
+        assert_eq!(d.messages.len(), 1);
+        assert_eq!(d.messages[0].module, Some("Main".to_owned()));
+        assert_eq!(d.messages[0].diag_type, DiagType::Error);
+        assert_eq!(
+            d.messages[0].msg,
+            "C09: Element G1 references private element Sub::G3 of module Sub."
+        );
+        assert_eq!(d.errors, 1);
+        assert_eq!(d.warnings, 0);
+    }
+
+    #[test]
+    fn no_module_interface() {
+        let mut d = Diagnostics::default();
+        let mut nodes = interface_nodes();
+        nodes.get_mut("Sub::G2").unwrap().public = None;
+        check_module_interfaces(&mut d, &nodes, &[]);
+        assert_eq!(d.messages.len(), 0);
+        assert_eq!(d.errors, 0);
+        assert_eq!(d.warnings, 0);
+    }
+
+    #[test]
+    fn private_reference_excluded_module() {
+        let mut d = Diagnostics::default();
+        let nodes = interface_nodes();
+        check_module_interfaces(&mut d, &nodes, &["Main"]);
+        assert_eq!(d.messages.len(), 0);
+    }
+
+    #[test]
+    fn unresolved_defeater() {
+        let mut d = Diagnostics::default();
+        let mut nodes = MyMap::<String, GsnNode>::new();
+        nodes.insert(
+            "G1".to_owned(),
+            GsnNode {
+                supported_by: Some(vec!["Sn1".to_owned()]),
+                ..Default::default()
+            },
+        );
+        nodes.insert("Sn1".to_owned(), GsnNode::default());
+        nodes.insert(
+            "CG1".to_owned(),
+            GsnNode {
+                node_type: Some("counterGoal".to_owned()),
+                challenges: Some("G1 -> Sn1".to_owned()),
+                ..Default::default()
+            },
+        );
+        nodes.insert(
+            "CG2".to_owned(),
+            GsnNode {
+                node_type: Some("counterGoal".to_owned()),
+                challenges: Some("G1".to_owned()),
+                ..Default::default()
+            },
+        );
+        nodes.insert(
+            "CS1".to_owned(),
+            GsnNode {
+                node_type: Some("counterSolution".to_owned()),
+                challenges: Some("CG1".to_owned()),
+                ..Default::default()
+            },
+        );
+        check_nodes(&mut d, &nodes, None);
+        assert_eq!(d.messages.len(), 1);
+        assert_eq!(d.messages[0].diag_type, DiagType::Warning);
+        assert_eq!(d.messages[0].msg, "C10: Defeater CG2 is unresolved.");
+        assert_eq!(d.errors, 0);
+        assert_eq!(d.warnings, 1);
+    }
+
+    #[test]
+    fn defeater_with_rebutted_challenger() {
+        let mut d = Diagnostics::default();
+        let mut nodes = MyMap::<String, GsnNode>::new();
+        let defeater = |node_type: &str, challenges: &str| GsnNode {
+            node_type: Some(node_type.to_owned()),
+            challenges: Some(challenges.to_owned()),
+            ..Default::default()
+        };
+        nodes.insert(
+            "G1".to_owned(),
+            GsnNode {
+                undeveloped: Some(true),
+                ..Default::default()
+            },
+        );
+        nodes.insert("CG1".to_owned(), defeater("counterGoal", "G1"));
+        nodes.insert("CG2".to_owned(), defeater("counterGoal", "CG1"));
+        nodes.insert("CS1".to_owned(), defeater("counterSolution", "CG2"));
+        // CG2 is rebutted by CS1, thus it does not resolve CG1.
+        check_nodes(&mut d, &nodes, None);
+        assert_eq!(d.messages.len(), 1);
+        assert_eq!(d.messages[0].msg, "C10: Defeater CG1 is unresolved.");
+    }
+
+    #[test]
+    fn unresolved_challenge() {
+        let mut d = Diagnostics::default();
+        let mut nodes = MyMap::<String, GsnNode>::new();
+        nodes.insert(
+            "G1".to_owned(),
+            GsnNode {
+                undeveloped: Some(true),
+                ..Default::default()
+            },
+        );
+        nodes.insert(
+            "CS1".to_owned(),
+            GsnNode {
+                node_type: Some("counterSolution".to_owned()),
+                challenges: Some("G2".to_owned()),
+                ..Default::default()
+            },
+        );
+        check_nodes(&mut d, &nodes, None);
+        assert_eq!(
+            d.messages[0].msg,
+            "C03: Element CS1 has unresolved challenged element: G2"
+        );
+        // CS1 is not attached to any element, thus it is unreachable.
+        assert!(d.messages[1].msg.starts_with("C08:"));
+        assert_eq!(d.errors, 2);
+    }
+
+    #[test]
+    fn confidence_below_threshold() {
+        use crate::gsn::confidence::Confidence;
+        let mut d = Diagnostics::default();
+        let mut nodes = MyMap::<String, GsnNode>::new();
+        nodes.insert(
+            "G1".to_owned(),
+            GsnNode {
+                supported_by: Some(vec!["Sn1".to_owned(), "Sn2".to_owned()]),
+                ..Default::default()
+            },
+        );
+        nodes.insert(
+            "Sn1".to_owned(),
+            GsnNode {
+                confidence: Some(Confidence::Value(0.9)),
+                ..Default::default()
+            },
+        );
+        nodes.insert(
+            "Sn2".to_owned(),
+            GsnNode {
+                confidence: Some(Confidence::Value(0.5)),
+                ..Default::default()
+            },
+        );
+        check_confidence(&mut d, &nodes, 0.4);
+        assert_eq!(d.messages.len(), 0);
+        check_confidence(&mut d, &nodes, 0.5);
+        assert_eq!(d.messages.len(), 1);
+        assert_eq!(d.messages[0].diag_type, DiagType::Warning);
+        assert_eq!(
+            d.messages[0].msg,
+            "C11: Confidence 0.45 of goal G1 is below the threshold 0.5."
+        );
+    }
+
+    #[test]
+    fn level_order() {
+        let mut d = Diagnostics::default();
+        let mut nodes = MyMap::<String, GsnNode>::new();
+        let node = |level: &str, supported_by: &[&str]| GsnNode {
+            level: (!level.is_empty()).then_some(level.to_owned()),
+            supported_by: Some(supported_by.iter().map(|s| s.to_string()).collect()),
+            module: "main".to_owned(),
+            ..Default::default()
+        };
+        nodes.insert("G1".to_owned(), node("top", &["S1", "G4"]));
+        nodes.insert("S1".to_owned(), node("middle", &["G2", "G3"]));
+        nodes.insert("G2".to_owned(), node("", &["G3"]));
+        nodes.insert("G3".to_owned(), node("bottom", &["G1", "G4"]));
+        nodes.insert("G4".to_owned(), node("bottom", &["Unresolved"]));
+        let mut modules = HashMap::new();
+        modules.insert(
+            "main".to_owned(),
+            Module {
+                filename: "main.gsn.yaml".to_owned(),
+                meta: Some(crate::gsn::ModuleInformation {
+                    name: "main".to_owned(),
+                    brief: None,
+                    pattern: None,
+                    confidence_for: None,
+                    levels: Some(vec![
+                        "top".to_owned(),
+                        "middle".to_owned(),
+                        "bottom".to_owned(),
+                    ]),
+                    additional: MyMap::new(),
+                }),
+            },
+        );
+        check_level_order(&mut d, &nodes, &modules);
+        let messages: Vec<&str> = d.messages.iter().map(|m| m.msg.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "C12: Element G3 on level bottom is supported by element G1 on level top, which is above.",
+                "C12: Element G3 on level bottom is supported by element G4 on level bottom, which is the same level.",
+            ]
+        );
+        assert_eq!(d.errors, 2);
+        // Suppressed for the supported element
+        let mut d = Diagnostics::default();
+        nodes.get_mut("G3").unwrap().suppress = Some(vec!["C12".to_owned()]);
+        d.suppressions = get_suppressions(&nodes);
+        check_level_order(&mut d, &nodes, &modules);
+        assert!(d.messages.is_empty());
+        // Without declared levels, the order is not checked.
+        let mut d = Diagnostics::default();
+        modules.get_mut("main").unwrap().meta = None;
+        check_level_order(&mut d, &nodes, &modules);
+        assert!(d.messages.is_empty());
+    }
+
+    #[test]
+    fn evidence_paths() {
+        assert_eq!(
+            get_evidence_path("https://example.com/report.pdf", "a/main.gsn.yaml"),
+            None
+        );
+        assert_eq!(
+            get_evidence_path("mailto:me@example.com", "a/main.gsn.yaml"),
+            None
+        );
+        assert_eq!(get_evidence_path("#top", "a/main.gsn.yaml"), None);
+        assert_eq!(
+            get_evidence_path("reports/test.pdf#page=3", "a/main.gsn.yaml"),
+            Some(PathBuf::from("a/reports/test.pdf"))
+        );
+        assert_eq!(
+            get_evidence_path("test.pdf", "main.gsn.yaml"),
+            Some(PathBuf::from("test.pdf"))
+        );
+        assert_eq!(
+            get_evidence_path("file:///b/test%20report.pdf#page=3", "a/main.gsn.yaml"),
+            Some(PathBuf::from("/b/test report.pdf"))
+        );
+        assert_eq!(
+            get_evidence_path("FILE://localhost/b/test.pdf", "a/main.gsn.yaml"),
+            Some(PathBuf::from("/b/test.pdf"))
+        );
+        assert_eq!(
+            get_evidence_path("file:///C:/b/test.pdf", "a/main.gsn.yaml"),
+            Some(PathBuf::from("C:/b/test.pdf"))
+        );
+        assert_eq!(
+            get_evidence_path("file://server/b/test.pdf", "a/main.gsn.yaml"),
+            None
+        );
+    }
+
+    #[test]
+    fn evidence_files() {
+        use assert_fs::prelude::*;
+        let temp = assert_fs::TempDir::new().unwrap();
+        let report = temp.child("report.txt");
+        report.write_str("abc").unwrap();
+        let module_file = temp.child("main.gsn.yaml");
+        let mut modules = HashMap::new();
+        modules.insert(
+            "main".to_owned(),
+            Module {
+                filename: module_file.path().to_str().unwrap().to_owned(),
+                meta: None,
+            },
+        );
+        modules.insert(
+            "excluded".to_owned(),
+            Module {
+                filename: module_file.path().to_str().unwrap().to_owned(),
+                meta: None,
+            },
+        );
+        let node = |url: &str, sha256: Option<&str>| GsnNode {
+            url: Some(url.to_owned()),
+            sha256: sha256.map(|s| s.to_owned()),
+            module: "main".to_owned(),
+            ..Default::default()
+        };
+        let mut nodes = MyMap::<String, GsnNode>::new();
+        nodes.insert(
+            "Sn1".to_owned(),
+            node(
+                "report.txt",
+                Some("BA7816BF8F01CFEA414140DE5DAE2223B00361A396177A9CB410FF61F20015AD"),
+            ),
+        );
+        nodes.insert("Sn2".to_owned(), node("missing.txt", None));
+        nodes.insert("Sn3".to_owned(), node("report.txt", Some("00")));
+        nodes.insert(
+            "Sn4".to_owned(),
+            node(&format!("file://{}", report.path().display()), None),
+        );
+        // Only solutions are evidence.
+        nodes.insert("C1".to_owned(), node("missing.txt", None));
+        nodes.insert(
+            "Sn5".to_owned(),
+            GsnNode {
+                module: "excluded".to_owned(),
+                ..node("missing.txt", None)
+            },
+        );
+        let mut d = Diagnostics::default();
+        check_evidence_files(&mut d, &nodes, &modules, &["excluded"]);
+        assert_eq!(d.errors, 2);
+        assert!(d.messages[0].msg.starts_with("C13: Evidence file "));
+        assert!(d.messages[0]
+            .msg
+            .ends_with("missing.txt of element Sn2 does not exist."));
+        assert!(d.messages[1].msg.ends_with(
+            "report.txt of element Sn3 has changed. Its sha256 is ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad, but 00 is recorded."
+        ));
+    }
 }
@@ -0,0 +1,314 @@
+use super::{GsnNode, GsnNodeType};
+use crate::yaml_fix::MyMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Display;
+
+///
+/// Confidence in a solution
+///
+/// Either a single value or an opinion of subjective logic.
+///
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(untagged)]
+pub enum Confidence {
+    Value(f64),
+    Opinion {
+        belief: f64,
+        disbelief: f64,
+        uncertainty: f64,
+    },
+}
+
+impl Confidence {
+    ///
+    /// Check if the confidence is well-formed
+    /// i.e., all values are between 0 and 1 and an opinion sums up to 1.
+    ///
+    pub fn is_valid(&self) -> bool {
+        let in_range = |x: &f64| (0.0..=1.0).contains(x);
+        match self {
+            Confidence::Value(v) => in_range(v),
+            Confidence::Opinion {
+                belief,
+                disbelief,
+                uncertainty,
+            } => {
+                [belief, disbelief, uncertainty].into_iter().all(in_range)
+                    && (belief + disbelief + uncertainty - 1.0).abs() < 1e-6
+            }
+        }
+    }
+}
+
+///
+/// How a strategy combines the confidences of its supporting elements
+///
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Combination {
+    /// All supporting elements are needed
+    Conjunctive,
+    /// Any supporting element is sufficient
+    Disjunctive,
+    /// Weighted average, supporting elements without weight have weight 1
+    Weighted(MyMap<String, f64>),
+}
+
+///
+/// Opinion of subjective logic
+///
+/// A single confidence value `c` is the opinion without uncertainty `(c, 1 - c, 0)`.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Opinion {
+    pub(crate) belief: f64,
+    pub(crate) disbelief: f64,
+    pub(crate) uncertainty: f64,
+}
+
+impl Opinion {
+    ///
+    /// The opinion if nothing is known.
+    ///
+    pub fn vacuous() -> Self {
+        Opinion {
+            belief: 0.0,
+            disbelief: 0.0,
+            uncertainty: 1.0,
+        }
+    }
+
+    ///
+    /// Conjunction (multiplication) of two opinions.
+    ///
+    fn and(&self, other: &Opinion) -> Opinion {
+        Opinion {
+            belief: self.belief * other.belief,
+            disbelief: self.disbelief + other.disbelief - self.disbelief * other.disbelief,
+            uncertainty: self.belief * other.uncertainty
+                + self.uncertainty * other.belief
+                + self.uncertainty * other.uncertainty,
+        }
+    }
+
+    ///
+    /// Disjunction (comultiplication) of two opinions.
+    ///
+    fn or(&self, other: &Opinion) -> Opinion {
+        Opinion {
+            belief: self.belief + other.belief - self.belief * other.belief,
+            disbelief: self.disbelief * other.disbelief,
+            uncertainty: self.disbelief * other.uncertainty
+                + self.uncertainty * other.disbelief
+                + self.uncertainty * other.uncertainty,
+        }
+    }
+}
+
+impl From<Confidence> for Opinion {
+    fn from(c: Confidence) -> Self {
+        match c {
+            Confidence::Value(v) => Opinion {
+                belief: v,
+                disbelief: 1.0 - v,
+                uncertainty: 0.0,
+            },
+            Confidence::Opinion {
+                belief,
+                disbelief,
+                uncertainty,
+            } => Opinion {
+                belief,
+                disbelief,
+                uncertainty,
+            },
+        }
+    }
+}
+
+impl Display for Opinion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.uncertainty.abs() < 1e-6 {
+            write!(f, "{:.2}", self.belief)
+        } else {
+            write!(
+                f,
+                "{:.2}/{:.2}/{:.2}",
+                self.belief, self.disbelief, self.uncertainty
+            )
+        }
+    }
+}
+
+///
+/// Check if any solution has a confidence.
+///
+pub fn has_confidences(nodes: &MyMap<String, GsnNode>) -> bool {
+    nodes.values().any(|n| n.confidence.is_some())
+}
+
+///
+/// Propagate the confidences of the solutions bottom-up along `supportedBy`.
+///
+/// Goals combine their supporting elements conjunctively,
+/// strategies as declared with `combination`.
+/// Elements without supporting elements e.g., undeveloped ones, are vacuous.
+/// Cycles are reported elsewhere (C04); an element closing a cycle is taken as vacuous.
+///
+pub fn get_confidences(nodes: &MyMap<String, GsnNode>) -> BTreeMap<String, Opinion> {
+    let mut confidences = BTreeMap::new();
+    for id in nodes.keys() {
+        calculate_confidence(nodes, id, &mut confidences, &mut BTreeSet::new());
+    }
+    confidences
+}
+
+///
+/// Calculate the confidence of a single element.
+/// Results are cached, since elements can be reached on several paths.
+///
+fn calculate_confidence(
+    nodes: &MyMap<String, GsnNode>,
+    id: &str,
+    cache: &mut BTreeMap<String, Opinion>,
+    visiting: &mut BTreeSet<String>,
+) -> Opinion {
+    if let Some(opinion) = cache.get(id) {
+        return *opinion;
+    }
+    let node = match nodes.get(id) {
+        Some(node) if visiting.insert(id.to_owned()) => node,
+        _ => return Opinion::vacuous(),
+    };
+    let opinion = if let Some(confidence) = node.confidence {
+        confidence.into()
+    } else {
+        let children: Vec<(&String, Opinion)> = node
+            .supported_by
+            .iter()
+            .flatten()
+            .map(|c| (c, calculate_confidence(nodes, c, cache, visiting)))
+            .collect();
+        let combination = match node.get_type(id) {
+            Some(GsnNodeType::Strategy) => node.combination.as_ref(),
+            _ => None,
+        };
+        match combination {
+            _ if children.is_empty() => Opinion::vacuous(),
+            None | Some(Combination::Conjunctive) => children
+                .iter()
+                .skip(1)
+                .fold(children[0].1, |acc, (_, o)| acc.and(o)),
+            Some(Combination::Disjunctive) => children
+                .iter()
+                .skip(1)
+                .fold(children[0].1, |acc, (_, o)| acc.or(o)),
+            Some(Combination::Weighted(weights)) => {
+                let weight = |c: &String| weights.get(c).copied().unwrap_or(1.0);
+                let sum: f64 = children.iter().map(|(c, _)| weight(c)).sum();
+                if sum > 0.0 {
+                    let avg = |f: fn(&Opinion) -> f64| {
+                        children.iter().map(|(c, o)| weight(c) * f(o)).sum::<f64>() / sum
+                    };
+                    Opinion {
+                        belief: avg(|o| o.belief),
+                        disbelief: avg(|o| o.disbelief),
+                        uncertainty: avg(|o| o.uncertainty),
+                    }
+                } else {
+                    Opinion::vacuous()
+                }
+            }
+        }
+    };
+    visiting.remove(id);
+    cache.insert(id.to_owned(), opinion);
+    opinion
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn node(supported_by: &[&str], confidence: Option<Confidence>) -> GsnNode {
+        GsnNode {
+            supported_by: if supported_by.is_empty() {
+                None
+            } else {
+                Some(supported_by.iter().map(|s| s.to_string()).collect())
+            },
+            confidence,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn valid_confidence() {
+        assert!(Confidence::Value(0.5).is_valid());
+        assert!(!Confidence::Value(1.5).is_valid());
+        assert!(Confidence::Opinion {
+            belief: 0.5,
+            disbelief: 0.2,
+            uncertainty: 0.3
+        }
+        .is_valid());
+        assert!(!Confidence::Opinion {
+            belief: 0.5,
+            disbelief: 0.5,
+            uncertainty: 0.5
+        }
+        .is_valid());
+    }
+
+    #[test]
+    fn propagate_values() {
+        let mut nodes = MyMap::<String, GsnNode>::new();
+        nodes.insert("G1".to_owned(), node(&["S1", "S2"], None));
+        let mut s1 = node(&["Sn1", "Sn2"], None);
+        s1.combination = Some(Combination::Disjunctive);
+        nodes.insert("S1".to_owned(), s1);
+        let mut s2 = node(&["Sn1", "Sn2"], None);
+        let mut weights = MyMap::new();
+        weights.insert("Sn1".to_owned(), 3.0);
+        s2.combination = Some(Combination::Weighted(weights));
+        nodes.insert("S2".to_owned(), s2);
+        nodes.insert("Sn1".to_owned(), node(&[], Some(Confidence::Value(0.5))));
+        nodes.insert("Sn2".to_owned(), node(&[], Some(Confidence::Value(0.9))));
+        let confidences = get_confidences(&nodes);
+        assert_eq!(confidences.get("S1").unwrap().to_string(), "0.95");
+        assert_eq!(confidences.get("S2").unwrap().to_string(), "0.60");
+        assert_eq!(confidences.get("G1").unwrap().to_string(), "0.57");
+    }
+
+    #[test]
+    fn propagate_opinions() {
+        let mut nodes = MyMap::<String, GsnNode>::new();
+        nodes.insert("G1".to_owned(), node(&["Sn1", "G2"], None));
+        nodes.insert("G2".to_owned(), node(&[], None));
+        nodes.insert(
+            "Sn1".to_owned(),
+            node(
+                &[],
+                Some(Confidence::Opinion {
+                    belief: 0.6,
+                    disbelief: 0.2,
+                    uncertainty: 0.2,
+                }),
+            ),
+        );
+        let confidences = get_confidences(&nodes);
+        assert_eq!(confidences.get("G2").unwrap().to_string(), "0.00/0.00/1.00");
+        assert_eq!(confidences.get("G1").unwrap().to_string(), "0.00/0.20/0.80");
+    }
+
+    #[test]
+    fn propagate_cycle() {
+        let mut nodes = MyMap::<String, GsnNode>::new();
+        nodes.insert("G1".to_owned(), node(&["G2"], None));
+        nodes.insert("G2".to_owned(), node(&["G1"], None));
+        let confidences = get_confidences(&nodes);
+        assert_eq!(confidences.get("G1").unwrap().to_string(), "0.00/0.00/1.00");
+        assert_eq!(confidences.get("G2").unwrap().to_string(), "0.00/0.00/1.00");
+    }
+}
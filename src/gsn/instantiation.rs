@@ -0,0 +1,771 @@
+use super::{get_display_id, get_local_id, Choice, GsnNode, ModuleInformation, Multiplicity};
+use crate::diagnostics::Diagnostics;
+use crate::yaml_fix::MyMap;
+use anyhow::{anyhow, Context, Result};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+use std::str::FromStr;
+
+///
+/// Data to instantiate a pattern with.
+///
+/// Scalars are bound to placeholders everywhere in the pattern.
+/// Lists are bound item by item to the instances of elements with a multiplicity.
+///
+#[derive(Debug, Default, PartialEq)]
+pub struct PatternData {
+    pub(crate) scalars: BTreeMap<String, String>,
+    pub(crate) lists: BTreeMap<String, Vec<String>>,
+}
+
+impl PatternData {
+    ///
+    /// Read pattern data from a YAML or CSV (if the extension is `.csv`) file.
+    ///
+    pub fn from_file(filename: &str) -> Result<PatternData> {
+        let input = std::fs::read_to_string(filename)
+            .context(format!("Failed to open file {}", filename))?;
+        if filename.to_ascii_lowercase().ends_with(".csv") {
+            PatternData::from_csv(&input)
+        } else {
+            PatternData::from_yaml(&input)
+        }
+        .context(format!(
+            "Failed to read pattern data from file {}",
+            filename
+        ))
+    }
+
+    ///
+    /// Read pattern data from YAML.
+    /// Each key is a placeholder, its value is either a scalar or a list of scalars.
+    ///
+    pub fn from_yaml(input: &str) -> Result<PatternData> {
+        let map: MyMap<String, serde_yaml::Value> = serde_yaml::from_str(input)?;
+        let mut data = PatternData::default();
+        for (placeholder, value) in map.iter() {
+            match value {
+                serde_yaml::Value::Sequence(items) => {
+                    let items = items
+                        .iter()
+                        .map(|i| {
+                            scalar_to_string(i).ok_or_else(|| {
+                                anyhow!("Items of placeholder {} must be scalars.", placeholder)
+                            })
+                        })
+                        .collect::<Result<Vec<String>>>()?;
+                    data.lists.insert(placeholder.to_owned(), items);
+                }
+                value => {
+                    let value = scalar_to_string(value).ok_or_else(|| {
+                        anyhow!(
+                            "Placeholder {} must be a scalar or a list of scalars.",
+                            placeholder
+                        )
+                    })?;
+                    data.scalars.insert(placeholder.to_owned(), value);
+                }
+            }
+        }
+        Ok(data)
+    }
+
+    ///
+    /// Read pattern data from CSV.
+    /// The header line names the placeholders, each following line is one item.
+    ///
+    pub fn from_csv(input: &str) -> Result<PatternData> {
+        let mut lines = input.lines().filter(|l| !l.trim().is_empty());
+        let header = split_csv_line(
+            lines
+                .next()
+                .ok_or_else(|| anyhow!("Missing header line."))?,
+        );
+        let mut lists: Vec<Vec<String>> = vec![Vec::new(); header.len()];
+        for (line_no, line) in lines.enumerate() {
+            let fields = split_csv_line(line);
+            if fields.len() != header.len() {
+                return Err(anyhow!(
+                    "Line {} has {} fields, but the header has {}.",
+                    line_no + 2,
+                    fields.len(),
+                    header.len()
+                ));
+            }
+            fields
+                .into_iter()
+                .zip(lists.iter_mut())
+                .for_each(|(f, l)| l.push(f));
+        }
+        Ok(PatternData {
+            scalars: BTreeMap::new(),
+            lists: header.into_iter().zip(lists).collect(),
+        })
+    }
+}
+
+///
+/// Convert a YAML scalar to a string.
+///
+fn scalar_to_string(value: &serde_yaml::Value) -> Option<String> {
+    match value {
+        serde_yaml::Value::String(s) => Some(s.to_owned()),
+        serde_yaml::Value::Number(n) => Some(n.to_string()),
+        serde_yaml::Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+///
+/// Split a line of CSV into its fields.
+/// Fields can be quoted with `"`, quotes within quoted fields are escaped by doubling them.
+///
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field).trim().to_owned()),
+            c => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_owned());
+    fields
+}
+
+///
+/// Get all placeholders e.g., `{Hazard}` in a text.
+///
+fn get_placeholders(text: &str) -> BTreeSet<String> {
+    text.split('{')
+        .skip(1)
+        .filter_map(|s| s.split_once('}'))
+        .map(|(p, _)| p)
+        .filter(|p| {
+            !p.is_empty()
+                && p.chars()
+                    .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+        })
+        .map(|p| p.to_owned())
+        .collect()
+}
+
+///
+/// Get all placeholders in the strings of a structured value.
+///
+fn get_value_placeholders(value: &serde_yaml::Value) -> BTreeSet<String> {
+    match value {
+        serde_yaml::Value::String(s) => get_placeholders(s),
+        serde_yaml::Value::Sequence(seq) => seq.iter().flat_map(get_value_placeholders).collect(),
+        serde_yaml::Value::Mapping(map) => map
+            .iter()
+            .flat_map(|(_, v)| get_value_placeholders(v))
+            .collect(),
+        _ => BTreeSet::new(),
+    }
+}
+
+///
+/// Get all placeholders in the text, the url and the additional attributes of an element.
+///
+fn get_node_placeholders(node: &GsnNode) -> BTreeSet<String> {
+    let mut placeholders = get_placeholders(&node.text);
+    if let Some(url) = &node.url {
+        placeholders.append(&mut get_placeholders(url));
+    }
+    for value in node.additional.values() {
+        placeholders.append(&mut get_value_placeholders(value));
+    }
+    placeholders
+}
+
+///
+/// Replace all bound placeholders in a text.
+///
+fn substitute(text: &str, bindings: &BTreeMap<String, String>) -> String {
+    bindings.iter().fold(text.to_owned(), |t, (p, v)| {
+        t.replace(&format!("{{{}}}", p), v)
+    })
+}
+
+///
+/// Replace all placeholders in the strings of a structured value.
+///
+fn substitute_value(value: &mut serde_yaml::Value, bindings: &BTreeMap<String, String>) {
+    match value {
+        serde_yaml::Value::String(s) => *s = substitute(s, bindings),
+        serde_yaml::Value::Sequence(seq) => {
+            seq.iter_mut().for_each(|v| substitute_value(v, bindings))
+        }
+        serde_yaml::Value::Mapping(map) => map
+            .iter_mut()
+            .for_each(|(_, v)| substitute_value(v, bindings)),
+        _ => (),
+    }
+}
+
+///
+/// Instantiation of a pattern module into a new module.
+///
+struct Instantiation<'a> {
+    pattern: &'a str,
+    module: &'a str,
+    nodes: &'a MyMap<String, GsnNode>,
+    data: &'a PatternData,
+    instance: MyMap<String, GsnNode>,
+}
+
+impl<'a> Instantiation<'a> {
+    ///
+    /// Get all placeholders used in the element and all elements it references within the pattern.
+    ///
+    fn get_subtree_placeholders(
+        &self,
+        id: &str,
+        visited: &mut BTreeSet<String>,
+    ) -> BTreeSet<String> {
+        let mut placeholders = BTreeSet::new();
+        if !visited.insert(id.to_owned()) {
+            return placeholders;
+        }
+        if let Some(node) = self.nodes.get(id).filter(|n| n.module == self.pattern) {
+            placeholders.append(&mut get_node_placeholders(node));
+            for (child, _) in node.get_edges() {
+                placeholders.append(&mut self.get_subtree_placeholders(&child, visited));
+            }
+        }
+        placeholders
+    }
+
+    ///
+    /// Check if all placeholders of the element and the elements it references are bound.
+    ///
+    fn is_bound(&self, id: &str, bindings: &BTreeMap<String, String>) -> bool {
+        self.get_subtree_placeholders(id, &mut BTreeSet::new())
+            .iter()
+            .all(|p| bindings.contains_key(p))
+    }
+
+    ///
+    /// Check if the data selects an alternative of a choice.
+    /// An alternative is selected if it uses placeholders and all of them are bound
+    /// either by the current bindings or by a list in the data.
+    ///
+    fn is_selected(&self, id: &str, bindings: &BTreeMap<String, String>) -> bool {
+        let placeholders = self.get_subtree_placeholders(id, &mut BTreeSet::new());
+        !placeholders.is_empty()
+            && placeholders
+                .iter()
+                .all(|p| bindings.contains_key(p) || self.data.lists.contains_key(p))
+    }
+
+    ///
+    /// Check that the data selects as many alternatives as the choice allows.
+    ///
+    fn check_choice(
+        &self,
+        diag: &mut Diagnostics,
+        id: &str,
+        node: &GsnNode,
+        choice: &Choice,
+        bindings: &BTreeMap<String, String>,
+    ) {
+        let selected: Vec<&str> = choice
+            .elements
+            .iter()
+            .filter(|e| self.is_selected(e, bindings))
+            .map(|e| get_display_id(e, self.pattern))
+            .collect();
+        let (lower, upper) = choice.get_bounds();
+        if selected.len() < lower || upper.map(|u| selected.len() > u).unwrap_or(false) {
+            let allowed = match upper {
+                Some(upper) if upper == lower => lower.to_string(),
+                Some(upper) => format!("{} to {}", lower, upper),
+                None => format!("at least {}", lower),
+            };
+            diag.add_error_at(
+                Some(self.pattern),
+                Some(id),
+                node.location.as_ref(),
+                format!(
+                    "I04: Choice of element {} requires {} of {} alternatives, but the data selects {}{}.",
+                    get_display_id(id, self.pattern),
+                    allowed,
+                    choice.elements.len(),
+                    selected.len(),
+                    if selected.is_empty() {
+                        "".to_owned()
+                    } else {
+                        format!(": {}", selected.join(", "))
+                    }
+                ),
+            );
+        }
+    }
+
+    ///
+    /// Instantiate an element and, recursively, the elements it references.
+    /// Returns the identifier of the instance.
+    ///
+    fn instantiate_node(
+        &mut self,
+        diag: &mut Diagnostics,
+        id: &str,
+        suffix: &str,
+        bindings: &BTreeMap<String, String>,
+    ) -> String {
+        let node = match self.nodes.get(id).filter(|n| n.module == self.pattern) {
+            Some(node) => node,
+            // Elements of other modules are referenced as they are.
+            None => return id.to_owned(),
+        };
+        let new_id = format!("{}{}", get_local_id(id), suffix);
+        if self.instance.contains_key(&new_id) {
+            return new_id;
+        }
+        let mut instance = GsnNode {
+            text: substitute(&node.text, bindings),
+            url: node.url.as_ref().map(|u| substitute(u, bindings)),
+            uninstantiated: None,
+            multiplicity: None,
+            choice: None,
+            module: self.module.to_owned(),
+            // Entries of the reference lists change, thus, their locations are not kept.
+            reference_locations: BTreeMap::new(),
+            ..node.clone()
+        };
+        for value in instance.additional.values_mut() {
+            substitute_value(value, bindings);
+        }
+        for placeholder in get_node_placeholders(&instance) {
+            diag.add_error_at(
+                Some(self.pattern),
+                Some(id),
+                node.location.as_ref(),
+                format!(
+                    "I01: Placeholder {{{}}} of element {} is not bound by the data.",
+                    placeholder,
+                    get_display_id(id, self.pattern)
+                ),
+            );
+        }
+        if let Some(choice) = &node.choice {
+            self.check_choice(diag, id, node, choice, bindings);
+        }
+        // Insert first, such that elements referenced more than once are only instantiated once.
+        self.instance.insert(new_id.to_owned(), instance);
+        let in_context_of = node
+            .in_context_of
+            .as_ref()
+            .map(|refs| self.instantiate_references(diag, id, node, refs, suffix, bindings));
+        let supported_by = node
+            .supported_by
+            .as_ref()
+            .map(|refs| self.instantiate_references(diag, id, node, refs, suffix, bindings));
+        let instance = self.instance.get_mut(&new_id).unwrap();
+        instance.in_context_of = in_context_of.filter(|r| !r.is_empty());
+        instance.supported_by = supported_by.filter(|r| !r.is_empty());
+        // Assurance claim points are only kept for elements that are instantiated once.
+        instance.acp = instance
+            .acp
+            .take()
+            .filter(|_| suffix.is_empty())
+            .map(|acp| {
+                let mut kept = MyMap::new();
+                for (name, target) in acp.iter().filter(|(_, t)| {
+                    t.as_ref()
+                        .map(|t| instance.get_edges().iter().any(|(e, _)| e == t))
+                        .unwrap_or(true)
+                }) {
+                    kept.insert(name.to_owned(), target.to_owned());
+                }
+                kept
+            })
+            .filter(|acp| !acp.is_empty());
+        new_id
+    }
+
+    ///
+    /// Instantiate the referenced elements according to their multiplicity or choice.
+    ///
+    fn instantiate_references(
+        &mut self,
+        diag: &mut Diagnostics,
+        id: &str,
+        node: &GsnNode,
+        references: &[String],
+        suffix: &str,
+        bindings: &BTreeMap<String, String>,
+    ) -> Vec<String> {
+        let mut instances = Vec::new();
+        for reference in references {
+            let multiplicity = node
+                .multiplicity
+                .as_ref()
+                .and_then(|m| m.get(reference))
+                .and_then(|m| Multiplicity::from_str(m).ok());
+            let is_alternative = node
+                .choice
+                .as_ref()
+                .map(|c| c.elements.contains(reference))
+                .unwrap_or(false);
+            // Alternatives of a choice are only instantiated if the data selects them.
+            if is_alternative && !self.is_selected(reference, bindings) {
+                continue;
+            }
+            match &multiplicity {
+                Some(m @ Multiplicity::Multiple(_)) => {
+                    let (lower, upper) = m.get_bounds();
+                    let lists: Vec<(&String, &Vec<String>)> = self
+                        .get_subtree_placeholders(reference, &mut BTreeSet::new())
+                        .into_iter()
+                        .filter(|p| !bindings.contains_key(p))
+                        .filter_map(|p| self.data.lists.get_key_value(&p))
+                        .collect();
+                    let count = lists.first().map(|(_, l)| l.len()).unwrap_or(0);
+                    if lists.is_empty() {
+                        diag.add_error_at(
+                            Some(self.pattern),
+                            Some(id),
+                            node.get_reference_location(reference),
+                            format!(
+                                "I02: Multiplicity of element {} for {} requires a list in the data.",
+                                get_display_id(id, self.pattern),
+                                get_display_id(reference, self.pattern)
+                            ),
+                        );
+                    } else if lists.iter().any(|(_, l)| l.len() != count) {
+                        diag.add_error_at(
+                            Some(self.pattern),
+                            Some(id),
+                            node.get_reference_location(reference),
+                            format!(
+                                "I03: Lists for multiplicity of element {} for {} have different lengths.",
+                                get_display_id(id, self.pattern),
+                                get_display_id(reference, self.pattern)
+                            ),
+                        );
+                    } else if count < lower || upper.map(|u| count > u).unwrap_or(false) {
+                        diag.add_error_at(
+                            Some(self.pattern),
+                            Some(id),
+                            node.get_reference_location(reference),
+                            format!(
+                                "I05: Multiplicity of element {} for {} does not allow a list of length {} in the data.",
+                                get_display_id(id, self.pattern),
+                                get_display_id(reference, self.pattern),
+                                count
+                            ),
+                        );
+                    } else {
+                        for i in 0..count {
+                            let mut item_bindings = bindings.clone();
+                            for (p, l) in &lists {
+                                item_bindings.insert(p.to_string(), l[i].to_owned());
+                            }
+                            instances.push(self.instantiate_node(
+                                diag,
+                                reference,
+                                &format!("{}_{}", suffix, i + 1),
+                                &item_bindings,
+                            ));
+                        }
+                    }
+                }
+                // Optional elements are only instantiated if the data binds them.
+                Some(Multiplicity::Optional) => {
+                    if self.is_bound(reference, bindings) {
+                        instances.push(self.instantiate_node(diag, reference, suffix, bindings));
+                    }
+                }
+                None => instances.push(self.instantiate_node(diag, reference, suffix, bindings)),
+            }
+        }
+        instances
+    }
+}
+
+///
+/// Instantiate the pattern module `pattern` with `data` into a new module `module`.
+///
+/// Elements with a multiplicity are instantiated once per item of the lists in the data
+/// that are used in their placeholders. Their identifiers are suffixed with the number of the item.
+/// Optional elements are only instantiated, if all their placeholders are bound.
+/// Alternatives of a choice are only instantiated, if they use placeholders and all of them are bound.
+///
+pub fn instantiate(
+    diag: &mut Diagnostics,
+    pattern: &str,
+    module: &str,
+    nodes: &MyMap<String, GsnNode>,
+    data: &PatternData,
+) -> MyMap<String, GsnNode> {
+    let mut inst = Instantiation {
+        pattern,
+        module,
+        nodes,
+        data,
+        instance: MyMap::new(),
+    };
+    let referenced: BTreeSet<String> = nodes
+        .values()
+        .filter(|n| n.module == pattern)
+        .flat_map(|n| n.get_edges())
+        .map(|(target, _)| target)
+        .collect();
+    let roots: Vec<&String> = nodes
+        .iter()
+        .filter(|(id, n)| n.module == pattern && !referenced.contains(*id))
+        .map(|(id, _)| id)
+        .collect();
+    for root in roots {
+        inst.instantiate_node(diag, root, "", &data.scalars);
+    }
+    inst.instance
+}
+
+///
+/// Write an instantiated module as YAML.
+/// The module information is written first.
+///
+pub fn write_instance(
+    output: &mut impl Write,
+    meta: &ModuleInformation,
+    nodes: &MyMap<String, GsnNode>,
+) -> Result<()> {
+    let mut document = serde_yaml::Mapping::new();
+    document.insert(
+        serde_yaml::Value::from("module"),
+        serde_yaml::to_value(meta)?,
+    );
+    for (id, node) in nodes.iter() {
+        document.insert(
+            serde_yaml::Value::from(id.as_str()),
+            serde_yaml::to_value(node)?,
+        );
+    }
+    serde_yaml::to_writer(output, &document)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::gsn::check::check_nodes;
+    use crate::gsn::validation::validate_module;
+    use crate::gsn::Choice;
+
+    fn pattern_node(text: &str, supported_by: Option<Vec<&str>>) -> GsnNode {
+        GsnNode {
+            text: text.to_owned(),
+            supported_by: supported_by.map(|s| s.iter().map(|x| x.to_string()).collect()),
+            module: "Pattern".to_owned(),
+            ..Default::default()
+        }
+    }
+
+    fn hazard_pattern() -> MyMap<String, GsnNode> {
+        let mut nodes = MyMap::<String, GsnNode>::new();
+        nodes.insert(
+            "G1".to_owned(),
+            pattern_node("{System} is safe", Some(vec!["S1"])),
+        );
+        let mut s1 = pattern_node("Argument over each hazard", Some(vec!["G2", "G3", "G4"]));
+        let mut multiplicity = MyMap::new();
+        multiplicity.insert("G2".to_owned(), "n".to_owned());
+        multiplicity.insert("G3".to_owned(), "0..1".to_owned());
+        s1.multiplicity = Some(multiplicity);
+        s1.choice = Some(Choice {
+            elements: vec!["G3".to_owned(), "G4".to_owned()],
+            label: None,
+        });
+        nodes.insert("S1".to_owned(), s1);
+        nodes.insert(
+            "G2".to_owned(),
+            pattern_node("{Hazard} is mitigated", Some(vec!["Sn1"])),
+        );
+        nodes.insert(
+            "G3".to_owned(),
+            pattern_node("{Operation} is safe", Some(vec!["Sn2"])),
+        );
+        nodes.insert(
+            "G4".to_owned(),
+            pattern_node("{System} is simple", Some(vec!["Sn2"])),
+        );
+        nodes.insert("Sn1".to_owned(), pattern_node("Analysis of {Hazard}", None));
+        nodes.insert("Sn2".to_owned(), pattern_node("Review of {System}", None));
+        nodes
+    }
+
+    #[test]
+    fn placeholders() {
+        assert_eq!(
+            get_placeholders("{Hazard} of {System} {not a placeholder} {}"),
+            BTreeSet::from(["Hazard".to_owned(), "System".to_owned()])
+        );
+    }
+
+    #[test]
+    fn substitute_structured() {
+        let mut bindings = BTreeMap::new();
+        bindings.insert("System".to_owned(), "Boiler".to_owned());
+        let mut value: serde_yaml::Value =
+            serde_yaml::from_str("owner: {name: '{System} team'}\ntests: ['{System}-T1', 2]")
+                .unwrap();
+        substitute_value(&mut value, &bindings);
+        let expected: serde_yaml::Value =
+            serde_yaml::from_str("owner: {name: 'Boiler team'}\ntests: ['Boiler-T1', 2]").unwrap();
+        assert_eq!(value, expected);
+    }
+
+    #[test]
+    fn csv() {
+        let data = PatternData::from_csv(
+            "Hazard, Cause\n\"Fire, big\",Heat\nFlood,\"Rain \"\"heavy\"\"\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            data.lists.get("Hazard"),
+            Some(&vec!["Fire, big".to_owned(), "Flood".to_owned()])
+        );
+        assert_eq!(
+            data.lists.get("Cause"),
+            Some(&vec!["Heat".to_owned(), "Rain \"heavy\"".to_owned()])
+        );
+        assert!(PatternData::from_csv("A,B\n1\n").is_err());
+    }
+
+    #[test]
+    fn yaml() {
+        let data =
+            PatternData::from_yaml("System: Boiler\nHazard: [Fire, Flood]\nCount: 2").unwrap();
+        assert_eq!(data.scalars.get("System"), Some(&"Boiler".to_owned()));
+        assert_eq!(data.scalars.get("Count"), Some(&"2".to_owned()));
+        assert_eq!(
+            data.lists.get("Hazard"),
+            Some(&vec!["Fire".to_owned(), "Flood".to_owned()])
+        );
+        assert!(PatternData::from_yaml("System: {a: b}").is_err());
+    }
+
+    #[test]
+    fn instantiate_pattern() {
+        let mut d = Diagnostics::default();
+        let nodes = hazard_pattern();
+        let data = PatternData::from_yaml("System: Boiler\nHazard: [Fire, Flood]").unwrap();
+        let instance = instantiate(&mut d, "Pattern", "Boiler", &nodes, &data);
+        assert_eq!(d.messages.len(), 0);
+        assert_eq!(
+            instance.keys().cloned().collect::<Vec<String>>(),
+            vec!["G1", "G2_1", "G2_2", "G4", "S1", "Sn1_1", "Sn1_2", "Sn2"]
+        );
+        assert_eq!(instance.get("G1").unwrap().text, "Boiler is safe");
+        assert_eq!(instance.get("G2_2").unwrap().text, "Flood is mitigated");
+        assert_eq!(instance.get("Sn1_1").unwrap().text, "Analysis of Fire");
+        assert_eq!(
+            instance.get("S1").unwrap().supported_by,
+            Some(vec!["G2_1".to_owned(), "G2_2".to_owned(), "G4".to_owned()])
+        );
+        assert!(instance.get("S1").unwrap().multiplicity.is_none());
+        assert!(instance.values().all(|n| n.module == "Boiler"));
+        validate_module(&mut d, "Boiler", &instance);
+        check_nodes(&mut d, &instance, None);
+        assert_eq!(d.messages.len(), 0);
+    }
+
+    #[test]
+    fn unbound_placeholders() {
+        let mut d = Diagnostics::default();
+        let nodes = hazard_pattern();
+        let data = PatternData::from_yaml("Hazard: [Fire]\nOther: [A, B]").unwrap();
+        instantiate(&mut d, "Pattern", "Boiler", &nodes, &data);
+        assert_eq!(d.messages.len(), 2);
+        assert_eq!(
+            d.messages[0].msg,
+            "I01: Placeholder {System} of element G1 is not bound by the data."
+        );
+        assert_eq!(
+            d.messages[1].msg,
+            "I04: Choice of element S1 requires 1 of 2 alternatives, but the data selects 0."
+        );
+    }
+
+    #[test]
+    fn unbound_placeholders_in_attributes() {
+        let mut d = Diagnostics::default();
+        let mut nodes = MyMap::<String, GsnNode>::new();
+        let mut g1 = pattern_node("{System} is safe", None);
+        g1.url = Some("https://example.com/{Owner}".to_owned());
+        g1.additional.insert(
+            "reviewer".to_owned(),
+            serde_yaml::Value::from(vec!["{Reviewer}", "{System} team"]),
+        );
+        nodes.insert("G1".to_owned(), g1);
+        let data = PatternData::from_yaml("System: Boiler").unwrap();
+        instantiate(&mut d, "Pattern", "Boiler", &nodes, &data);
+        let messages: Vec<&str> = d.messages.iter().map(|m| m.msg.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "I01: Placeholder {Owner} of element G1 is not bound by the data.",
+                "I01: Placeholder {Reviewer} of element G1 is not bound by the data."
+            ]
+        );
+    }
+
+    #[test]
+    fn too_many_alternatives() {
+        let mut d = Diagnostics::default();
+        let nodes = hazard_pattern();
+        let data =
+            PatternData::from_yaml("System: Boiler\nOperation: Shutdown\nHazard: [Fire]").unwrap();
+        let instance = instantiate(&mut d, "Pattern", "Boiler", &nodes, &data);
+        assert_eq!(d.messages.len(), 1);
+        assert_eq!(
+            d.messages[0].msg,
+            "I04: Choice of element S1 requires 1 of 2 alternatives, but the data selects 2: G3, G4."
+        );
+        assert!(instance.contains_key("G3") && instance.contains_key("G4"));
+    }
+
+    #[test]
+    fn multiplicity_bounds() {
+        let mut d = Diagnostics::default();
+        let mut nodes = hazard_pattern();
+        nodes
+            .get_mut("S1")
+            .unwrap()
+            .multiplicity
+            .as_mut()
+            .unwrap()
+            .insert("G2".to_owned(), "at least 2".to_owned());
+        let data = PatternData::from_yaml("System: Boiler\nHazard: [Fire]").unwrap();
+        let instance = instantiate(&mut d, "Pattern", "Boiler", &nodes, &data);
+        assert_eq!(d.messages.len(), 1);
+        assert_eq!(
+            d.messages[0].msg,
+            "I05: Multiplicity of element S1 for G2 does not allow a list of length 1 in the data."
+        );
+        assert!(!instance.contains_key("G2_1"));
+    }
+
+    #[test]
+    fn missing_list() {
+        let mut d = Diagnostics::default();
+        let nodes = hazard_pattern();
+        let data = PatternData::from_yaml("System: Boiler").unwrap();
+        instantiate(&mut d, "Pattern", "Boiler", &nodes, &data);
+        assert_eq!(d.messages.len(), 1);
+        assert_eq!(
+            d.messages[0].msg,
+            "I02: Multiplicity of element S1 for G2 requires a list in the data."
+        );
+    }
+}
@@ -0,0 +1,465 @@
+use super::suggest::{format_suggestions, get_suggestions};
+use super::{get_display_id, get_node_type, GsnNode, GsnNodeType};
+use crate::diagnostics::Diagnostics;
+use crate::yaml_fix::MyMap;
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::collections::BTreeSet;
+
+///
+/// Codes and names of all lint rules
+///
+pub const RULES: [(&str, &str); 10] = [
+    ("L01", "single-child-strategy"),
+    ("L02", "too-many-solutions"),
+    ("L03", "context-of-solution"),
+    ("L04", "unreferenced-justification"),
+    ("L05", "unexplained-strategy"),
+    ("L06", "question-goal"),
+    ("L07", "goal-without-verb"),
+    ("L08", "claim-solution"),
+    ("L09", "long-text"),
+    ("L10", "placeholder-text"),
+];
+
+///
+/// Default words that make a text a proposition (L07, L08)
+///
+const DEFAULT_VERBS: [&str; 38] = [
+    "is",
+    "are",
+    "was",
+    "were",
+    "be",
+    "been",
+    "being",
+    "has",
+    "have",
+    "had",
+    "can",
+    "cannot",
+    "could",
+    "will",
+    "would",
+    "shall",
+    "should",
+    "may",
+    "might",
+    "must",
+    "does",
+    "do",
+    "did",
+    "meets",
+    "meet",
+    "satisfies",
+    "satisfy",
+    "holds",
+    "hold",
+    "ensures",
+    "ensure",
+    "complies",
+    "comply",
+    "fulfils",
+    "fulfills",
+    "provides",
+    "provide",
+    "achieves",
+];
+
+///
+/// Default placeholders for incomplete texts (L10)
+///
+const DEFAULT_PLACEHOLDERS: [&str; 5] = ["TODO", "TBD", "TBC", "FIXME", "XXX"];
+
+///
+/// Thresholds and word lists of the lint rules
+///
+/// They can be set in the `lint` section of the configuration file.
+///
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+pub struct LintOptions {
+    /// Maximum number of solutions directly supporting a goal (L02)
+    pub(crate) max_solutions: usize,
+    /// Maximum number of words of a text (L09)
+    pub(crate) max_words: usize,
+    /// Words that make a text a proposition (L07, L08)
+    pub(crate) verbs: Vec<String>,
+    /// Words that mark an incomplete text (L10)
+    pub(crate) placeholders: Vec<String>,
+}
+
+impl Default for LintOptions {
+    fn default() -> Self {
+        LintOptions {
+            max_solutions: 3,
+            max_words: 50,
+            verbs: DEFAULT_VERBS.iter().map(|v| v.to_string()).collect(),
+            placeholders: DEFAULT_PLACEHOLDERS.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+}
+
+///
+/// The enabled lint rules and their options
+///
+#[derive(Debug)]
+pub struct Lints {
+    pub(crate) rules: BTreeSet<&'static str>,
+    pub(crate) options: LintOptions,
+}
+
+impl Lints {
+    ///
+    /// Enable the rules in `selection` given by code or name.
+    /// All rules are enabled if `selection` is empty.
+    ///
+    pub fn new(selection: &[&str], options: LintOptions) -> Result<Self> {
+        let mut rules = BTreeSet::new();
+        for s in selection {
+            let (code, _) = RULES
+                .iter()
+                .find(|(code, name)| code.eq_ignore_ascii_case(s) || name == s)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Unknown lint rule {}{}",
+                        s,
+                        format_suggestions(&get_suggestions(
+                            s,
+                            RULES.iter().map(|(_, name)| *name)
+                        ))
+                    )
+                })?;
+            rules.insert(*code);
+        }
+        if rules.is_empty() {
+            rules.extend(RULES.iter().map(|(code, _)| *code));
+        }
+        Ok(Lints { rules, options })
+    }
+
+    ///
+    /// Check if the rule `code` is enabled.
+    ///
+    fn applies(&self, code: &str) -> bool {
+        self.rules.contains(code)
+    }
+}
+
+///
+/// Entry function to all lints.
+///
+/// Lints report deviations from structural and textual best practices as warnings.
+/// Elements of excluded modules are not linted.
+///
+pub fn lint_nodes(
+    diag: &mut Diagnostics,
+    nodes: &MyMap<String, GsnNode>,
+    lints: &Lints,
+    ex_mods: &[&str],
+) {
+    let referenced: BTreeSet<&String> = nodes
+        .values()
+        .flat_map(|n| {
+            n.supported_by
+                .iter()
+                .chain(n.in_context_of.iter())
+                .flatten()
+        })
+        .collect();
+    for (id, node) in nodes
+        .iter()
+        .filter(|(_, n)| !ex_mods.contains(&n.module.as_str()))
+    {
+        let name = get_display_id(id, &node.module);
+        let mut warn = |msg: String| {
+            diag.add_warning_at(Some(&node.module), Some(id), node.location.as_ref(), msg)
+        };
+        let count_of = |list: &Option<Vec<String>>, types: &[GsnNodeType]| {
+            list.iter()
+                .flatten()
+                .filter(|r| matches!(get_node_type(nodes, r), Some(t) if types.contains(&t)))
+                .count()
+        };
+        match node.get_type(id) {
+            Some(GsnNodeType::Strategy) => {
+                if lints.applies("L01") && node.supported_by.iter().flatten().count() == 1 {
+                    warn(format!(
+                        "L01: Strategy {} has only one supporting element.",
+                        name
+                    ));
+                }
+                if lints.applies("L05")
+                    && count_of(
+                        &node.in_context_of,
+                        &[GsnNodeType::Context, GsnNodeType::Justification],
+                    ) == 0
+                {
+                    warn(format!(
+                        "L05: Strategy {} is not explained by a context or justification.",
+                        name
+                    ));
+                }
+            }
+            Some(GsnNodeType::Goal) => {
+                let solutions = count_of(&node.supported_by, &[GsnNodeType::Solution]);
+                if lints.applies("L02") && solutions > lints.options.max_solutions {
+                    warn(format!(
+                        "L02: Goal {} is directly supported by {} solutions, more than {}.",
+                        name, solutions, lints.options.max_solutions
+                    ));
+                }
+            }
+            Some(GsnNodeType::Solution) if lints.applies("L03") && node.in_context_of.is_some() => {
+                warn(format!("L03: Solution {} has a context.", name));
+            }
+            Some(GsnNodeType::Justification)
+                if lints.applies("L04") && !referenced.contains(id) =>
+            {
+                warn(format!("L04: Justification {} is never referenced.", name));
+            }
+            _ => (),
+        }
+        lint_text(&mut warn, name, node, lints);
+    }
+}
+
+///
+/// Split `text` into words.
+///
+fn get_words(text: &str) -> Vec<&str> {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '\'' || c == '-'))
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+///
+/// Lint the text of an element.
+///
+/// The heuristics only look at single words, they do not analyze the grammar.
+///
+fn lint_text(warn: &mut impl FnMut(String), id: &str, node: &GsnNode, lints: &Lints) {
+    let words = get_words(&node.text);
+    let options = &lints.options;
+    let has_verb = || {
+        words
+            .iter()
+            .any(|w| options.verbs.iter().any(|v| v.eq_ignore_ascii_case(w)))
+    };
+    match node.get_type(id) {
+        Some(GsnNodeType::Goal) | Some(GsnNodeType::CounterGoal) => {
+            if lints.applies("L06") && node.text.trim_end().ends_with('?') {
+                warn(format!(
+                    "L06: Goal {} is phrased as a question, it should be a proposition.",
+                    id
+                ));
+            } else if lints.applies("L07") && !words.is_empty() && !has_verb() {
+                warn(format!(
+                    "L07: Goal {} has no verb, it should be a proposition.",
+                    id
+                ));
+            }
+        }
+        Some(GsnNodeType::Solution) | Some(GsnNodeType::CounterSolution)
+            if lints.applies("L08") && has_verb() =>
+        {
+            warn(format!(
+                "L08: Solution {} is phrased as a claim, it should name the evidence.",
+                id
+            ));
+        }
+        _ => (),
+    }
+    if lints.applies("L09") && words.len() > options.max_words {
+        warn(format!(
+            "L09: Text of element {} has {} words, more than {}.",
+            id,
+            words.len(),
+            options.max_words
+        ));
+    }
+    if lints.applies("L10") {
+        if let Some(placeholder) = words.iter().find(|w| {
+            options
+                .placeholders
+                .iter()
+                .any(|p| p.eq_ignore_ascii_case(w))
+        }) {
+            warn(format!(
+                "L10: Text of element {} contains placeholder {}.",
+                id, placeholder
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::gsn::get_suppressions;
+
+    fn node(supported_by: &[&str], in_context_of: &[&str]) -> GsnNode {
+        let list = |l: &[&str]| {
+            (!l.is_empty()).then_some(l.iter().map(|s| s.to_string()).collect::<Vec<_>>())
+        };
+        GsnNode {
+            supported_by: list(supported_by),
+            in_context_of: list(in_context_of),
+            ..Default::default()
+        }
+    }
+
+    fn nodes() -> MyMap<String, GsnNode> {
+        let mut nodes = MyMap::new();
+        nodes.insert("G1".to_owned(), node(&["S1"], &[]));
+        nodes.insert("S1".to_owned(), node(&["G2"], &["J1"]));
+        nodes.insert("S2".to_owned(), node(&["G2", "G3"], &["A1"]));
+        nodes.insert("G2".to_owned(), node(&["Sn1", "Sn2", "Sn3", "Sn4"], &[]));
+        nodes.insert("G3".to_owned(), node(&["Sn1"], &[]));
+        nodes.insert("Sn1".to_owned(), node(&[], &["C1"]));
+        nodes.insert("Sn2".to_owned(), node(&[], &[]));
+        nodes.insert("Sn3".to_owned(), node(&[], &[]));
+        nodes.insert("Sn4".to_owned(), node(&[], &[]));
+        nodes.insert("J1".to_owned(), node(&[], &[]));
+        nodes.insert("J2".to_owned(), node(&[], &[]));
+        nodes.insert("A1".to_owned(), node(&[], &[]));
+        nodes.insert("C1".to_owned(), node(&[], &[]));
+        nodes
+    }
+
+    #[test]
+    fn all_rules() {
+        let mut d = Diagnostics::default();
+        lint_nodes(
+            &mut d,
+            &nodes(),
+            &Lints::new(&[], LintOptions::default()).unwrap(),
+            &[],
+        );
+        let messages: Vec<&str> = d.messages.iter().map(|m| m.msg.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "L02: Goal G2 is directly supported by 4 solutions, more than 3.",
+                "L04: Justification J2 is never referenced.",
+                "L01: Strategy S1 has only one supporting element.",
+                "L05: Strategy S2 is not explained by a context or justification.",
+                "L03: Solution Sn1 has a context.",
+            ]
+        );
+        assert_eq!(d.warnings, 5);
+    }
+
+    #[test]
+    fn selected_rules() {
+        let mut d = Diagnostics::default();
+        let options = LintOptions {
+            max_solutions: 4,
+            ..Default::default()
+        };
+        let lints = Lints::new(&["l02", "context-of-solution"], options).unwrap();
+        lint_nodes(&mut d, &nodes(), &lints, &[]);
+        assert_eq!(d.messages.len(), 1);
+        assert_eq!(d.messages[0].msg, "L03: Solution Sn1 has a context.");
+    }
+
+    #[test]
+    fn suppressed_rule() {
+        let mut d = Diagnostics::default();
+        let mut nodes = nodes();
+        nodes.get_mut("J2").unwrap().suppress = Some(vec!["L04".to_owned()]);
+        d.suppressions = get_suppressions(&nodes);
+        lint_nodes(
+            &mut d,
+            &nodes,
+            &Lints::new(&["L04"], LintOptions::default()).unwrap(),
+            &[],
+        );
+        assert!(d.messages.is_empty());
+    }
+
+    #[test]
+    fn excluded_module() {
+        let mut d = Diagnostics::default();
+        let mut nodes = nodes();
+        for node in nodes.values_mut() {
+            node.module = "Sub".to_owned();
+        }
+        lint_nodes(
+            &mut d,
+            &nodes,
+            &Lints::new(&[], LintOptions::default()).unwrap(),
+            &["Sub"],
+        );
+        assert!(d.messages.is_empty());
+    }
+
+    #[test]
+    fn unknown_rule() {
+        let err = Lints::new(&["single-child-strategies"], LintOptions::default()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unknown lint rule single-child-strategies (did you mean single-child-strategy?)"
+        );
+    }
+
+    fn text_node(text: &str) -> GsnNode {
+        GsnNode {
+            text: text.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn text_rules() {
+        let mut d = Diagnostics::default();
+        let mut nodes = MyMap::new();
+        nodes.insert("G1".to_owned(), text_node("Is the system safe?"));
+        nodes.insert("G2".to_owned(), text_node("Safety of the system"));
+        nodes.insert("G3".to_owned(), text_node("The system is acceptably safe"));
+        nodes.insert("Sn1".to_owned(), text_node("Test results"));
+        nodes.insert("Sn2".to_owned(), text_node("The system has been tested"));
+        nodes.insert("C1".to_owned(), text_node("Operating context: TBD"));
+        nodes.insert("C2".to_owned(), text_node(&"word ".repeat(51)));
+        nodes.insert("C3".to_owned(), text_node("Operating modes: Todo"));
+        lint_nodes(
+            &mut d,
+            &nodes,
+            &Lints::new(&[], LintOptions::default()).unwrap(),
+            &[],
+        );
+        let messages: Vec<&str> = d.messages.iter().map(|m| m.msg.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "L10: Text of element C1 contains placeholder TBD.",
+                "L09: Text of element C2 has 51 words, more than 50.",
+                "L10: Text of element C3 contains placeholder Todo.",
+                "L06: Goal G1 is phrased as a question, it should be a proposition.",
+                "L07: Goal G2 has no verb, it should be a proposition.",
+                "L08: Solution Sn2 is phrased as a claim, it should name the evidence.",
+            ]
+        );
+    }
+
+    #[test]
+    fn text_options() {
+        let options: LintOptions =
+            serde_yaml::from_str("maxWords: 3\nverbs: [of]\nplaceholders: [tbd]\n").unwrap();
+        assert_eq!(options.max_solutions, 3);
+        let mut d = Diagnostics::default();
+        let mut nodes = MyMap::new();
+        nodes.insert("G1".to_owned(), text_node("Safety of the system"));
+        nodes.insert("C1".to_owned(), text_node("Context TBD"));
+        lint_nodes(&mut d, &nodes, &Lints::new(&[], options).unwrap(), &[]);
+        let messages: Vec<&str> = d.messages.iter().map(|m| m.msg.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "L10: Text of element C1 contains placeholder TBD.",
+                "L09: Text of element G1 has 4 words, more than 3.",
+            ]
+        );
+        assert!(serde_yaml::from_str::<LintOptions>("max_lines: 3\n").is_err());
+    }
+}
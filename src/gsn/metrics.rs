@@ -0,0 +1,377 @@
+use super::suggest::{format_suggestions, get_suggestions};
+use super::{GsnNode, GsnNodeType};
+use crate::diagnostics::Diagnostics;
+use crate::yaml_fix::MyMap;
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+///
+/// Names of all metrics that can be used for thresholds
+///
+pub const METRIC_NAMES: [&str; 8] = [
+    "depth",
+    "maxBranching",
+    "averageBranching",
+    "undevelopedGoals",
+    "solutions",
+    "minEvidencePerSubgoal",
+    "awayReferences",
+    "unresolvedReferences",
+];
+
+///
+/// Metrics of an argument or a single module
+///
+#[derive(Debug, Default, PartialEq)]
+pub struct Metrics {
+    /// Number of elements on the longest `supportedBy` path
+    pub(crate) depth: usize,
+    /// Maximum number of supporting elements of a strategy
+    pub(crate) max_branching: usize,
+    /// Average number of supporting elements of a strategy
+    pub(crate) average_branching: f64,
+    /// Percentage of leaf goals that are undeveloped
+    pub(crate) undeveloped_goals: f64,
+    /// Number of solutions
+    pub(crate) solutions: usize,
+    /// Number of solutions below each top-level subgoal
+    pub(crate) evidence_per_subgoal: BTreeMap<String, usize>,
+    /// Number of references to elements of other modules
+    pub(crate) away_references: usize,
+    /// Number of references to elements that are not found e.g., in modules that are not provided
+    pub(crate) unresolved_references: usize,
+}
+
+impl Metrics {
+    ///
+    /// Get the value of the metric `name`.
+    /// None is returned for `minEvidencePerSubgoal` if there are no top-level subgoals.
+    ///
+    pub fn get(&self, name: &str) -> Option<f64> {
+        match name {
+            "depth" => Some(self.depth as f64),
+            "maxBranching" => Some(self.max_branching as f64),
+            "averageBranching" => Some(self.average_branching),
+            "undevelopedGoals" => Some(self.undeveloped_goals),
+            "solutions" => Some(self.solutions as f64),
+            "minEvidencePerSubgoal" => self.evidence_per_subgoal.values().min().map(|m| *m as f64),
+            "awayReferences" => Some(self.away_references as f64),
+            "unresolvedReferences" => Some(self.unresolved_references as f64),
+            _ => None,
+        }
+    }
+}
+
+///
+/// Allowed range of a metric, both bounds are inclusive
+///
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Threshold {
+    pub(crate) min: Option<f64>,
+    pub(crate) max: Option<f64>,
+}
+
+///
+/// Get the metrics of `module` or of all elements if `module` is None.
+///
+/// Only references between the considered elements are followed.
+/// The graph must be free of cycles.
+///
+pub fn get_metrics(nodes: &MyMap<String, GsnNode>, module: Option<&str>) -> Metrics {
+    let selected: BTreeMap<&str, &GsnNode> = nodes
+        .iter()
+        .filter(|(_, n)| module.map(|m| n.module == m).unwrap_or(true))
+        .map(|(id, n)| (id.as_str(), n))
+        .collect();
+    let is_type = |id: &str, t: GsnNodeType| {
+        selected
+            .get(id)
+            .map(|n| n.get_type(id) == Some(t))
+            .unwrap_or(false)
+    };
+    let children = |id: &str| -> Vec<&str> {
+        selected
+            .get(id)
+            .into_iter()
+            .flat_map(|n| n.supported_by.iter().flatten())
+            .filter(|c| selected.contains_key(c.as_str()))
+            .map(|c| c.as_str())
+            .collect()
+    };
+    let mut metrics = Metrics::default();
+
+    let mut depths = BTreeMap::new();
+    for id in selected.keys() {
+        metrics.depth = metrics.depth.max(get_depth(id, &children, &mut depths));
+    }
+
+    let branching: Vec<usize> = selected
+        .keys()
+        .filter(|id| is_type(id, GsnNodeType::Strategy))
+        .map(|id| children(id).len())
+        .collect();
+    metrics.max_branching = branching.iter().copied().max().unwrap_or(0);
+    if !branching.is_empty() {
+        metrics.average_branching = branching.iter().sum::<usize>() as f64 / branching.len() as f64;
+    }
+
+    // Leaf goals are only supported by solutions, if at all.
+    let leaf_goals: Vec<&&GsnNode> = selected
+        .iter()
+        .filter(|(id, _)| is_type(id, GsnNodeType::Goal))
+        .filter(|(id, _)| {
+            !children(id)
+                .iter()
+                .any(|c| is_type(c, GsnNodeType::Goal) || is_type(c, GsnNodeType::Strategy))
+        })
+        .map(|(_, n)| n)
+        .collect();
+    if !leaf_goals.is_empty() {
+        // Goals without supporting elements are undeveloped, even if not marked (V02).
+        let undeveloped = leaf_goals
+            .iter()
+            .filter(|n| n.undeveloped == Some(true) || n.supported_by.is_none())
+            .count();
+        metrics.undeveloped_goals = 100.0 * undeveloped as f64 / leaf_goals.len() as f64;
+    }
+
+    metrics.solutions = selected
+        .keys()
+        .filter(|id| is_type(id, GsnNodeType::Solution))
+        .count();
+
+    // Top-level subgoals are the first goals below the root goals, possibly via strategies.
+    let supported: BTreeSet<&str> = selected.keys().flat_map(|id| children(id)).collect();
+    let mut subgoals = BTreeSet::new();
+    let mut stack: Vec<&str> = selected
+        .keys()
+        .filter(|id| !supported.contains(*id) && is_type(id, GsnNodeType::Goal))
+        .flat_map(|id| children(id))
+        .collect();
+    while let Some(id) = stack.pop() {
+        if is_type(id, GsnNodeType::Goal) {
+            subgoals.insert(id);
+        } else if is_type(id, GsnNodeType::Strategy) {
+            stack.append(&mut children(id));
+        }
+    }
+    for subgoal in subgoals {
+        let mut visited = BTreeSet::from([subgoal]);
+        let mut stack = vec![subgoal];
+        while let Some(id) = stack.pop() {
+            for child in children(id) {
+                if visited.insert(child) {
+                    stack.push(child);
+                }
+            }
+        }
+        let solutions = visited
+            .iter()
+            .filter(|id| is_type(id, GsnNodeType::Solution))
+            .count();
+        metrics
+            .evidence_per_subgoal
+            .insert(subgoal.to_owned(), solutions);
+    }
+
+    for node in selected.values() {
+        for reference in node
+            .supported_by
+            .iter()
+            .chain(node.in_context_of.iter())
+            .flatten()
+        {
+            match nodes.get(reference) {
+                Some(target) if target.module != node.module => metrics.away_references += 1,
+                Some(_) => (),
+                None => metrics.unresolved_references += 1,
+            }
+        }
+    }
+    metrics
+}
+
+///
+/// Get the number of elements on the longest path starting at `id`.
+/// Results are memorized in `depths`.
+///
+fn get_depth<'a>(
+    id: &'a str,
+    children: &impl Fn(&str) -> Vec<&'a str>,
+    depths: &mut BTreeMap<&'a str, usize>,
+) -> usize {
+    if let Some(depth) = depths.get(id) {
+        return *depth;
+    }
+    // Guard against cycles, they are reported elsewhere (C04).
+    depths.insert(id, 1);
+    let depth = 1 + children(id)
+        .into_iter()
+        .map(|c| get_depth(c, children, depths))
+        .max()
+        .unwrap_or(0);
+    depths.insert(id, depth);
+    depth
+}
+
+///
+/// Check the `metrics` against the configured `thresholds`.
+///
+/// Each metric outside its allowed range is reported as error.
+/// An error is returned for unknown metrics.
+///
+pub fn check_thresholds(
+    diag: &mut Diagnostics,
+    metrics: &Metrics,
+    thresholds: &BTreeMap<String, Threshold>,
+) -> Result<()> {
+    for (name, threshold) in thresholds {
+        if !METRIC_NAMES.contains(&name.as_str()) {
+            return Err(anyhow!(
+                "Unknown metric {}{}",
+                name,
+                format_suggestions(&get_suggestions(name, METRIC_NAMES))
+            ));
+        }
+        if let Some(value) = metrics.get(name) {
+            if let Some(min) = threshold.min.filter(|min| value < *min) {
+                diag.add_error(
+                    None,
+                    format!(
+                        "M01: Metric {} is {}, below the minimum of {}.",
+                        name, value, min
+                    ),
+                );
+            }
+            if let Some(max) = threshold.max.filter(|max| value > *max) {
+                diag.add_error(
+                    None,
+                    format!(
+                        "M01: Metric {} is {}, above the maximum of {}.",
+                        name, value, max
+                    ),
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn node(module: &str, supported_by: &[&str], in_context_of: &[&str]) -> GsnNode {
+        let list = |l: &[&str]| {
+            (!l.is_empty()).then_some(l.iter().map(|s| s.to_string()).collect::<Vec<_>>())
+        };
+        GsnNode {
+            module: module.to_owned(),
+            supported_by: list(supported_by),
+            in_context_of: list(in_context_of),
+            ..Default::default()
+        }
+    }
+
+    fn nodes() -> MyMap<String, GsnNode> {
+        let mut nodes = MyMap::new();
+        nodes.insert("G1".to_owned(), node("main", &["S1"], &["C1"]));
+        nodes.insert("C1".to_owned(), node("main", &[], &[]));
+        nodes.insert("S1".to_owned(), node("main", &["G2", "G3", "G4"], &[]));
+        nodes.insert("G2".to_owned(), node("main", &["Sn1", "Sn2"], &[]));
+        nodes.insert("G3".to_owned(), node("main", &["Sub::G5"], &[]));
+        nodes.insert(
+            "G4".to_owned(),
+            GsnNode {
+                undeveloped: Some(true),
+                ..node("main", &[], &["Missing::C2"])
+            },
+        );
+        nodes.insert("Sn1".to_owned(), node("main", &[], &[]));
+        nodes.insert("Sn2".to_owned(), node("main", &[], &[]));
+        nodes.insert("Sub::G5".to_owned(), node("Sub", &["Sub::S2"], &[]));
+        nodes.insert("Sub::S2".to_owned(), node("Sub", &["Sub::Sn3"], &[]));
+        nodes.insert("Sub::Sn3".to_owned(), node("Sub", &[], &[]));
+        nodes
+    }
+
+    #[test]
+    fn overall() {
+        let metrics = get_metrics(&nodes(), None);
+        assert_eq!(metrics.depth, 6);
+        assert_eq!(metrics.max_branching, 3);
+        assert_eq!(metrics.average_branching, 2.0);
+        assert_eq!(metrics.undeveloped_goals, 50.0);
+        assert_eq!(metrics.solutions, 3);
+        assert_eq!(
+            metrics.evidence_per_subgoal,
+            BTreeMap::from([
+                ("G2".to_owned(), 2),
+                ("G3".to_owned(), 1),
+                ("G4".to_owned(), 0)
+            ])
+        );
+        assert_eq!(metrics.away_references, 1);
+        assert_eq!(metrics.unresolved_references, 1);
+        assert_eq!(metrics.get("minEvidencePerSubgoal"), Some(0.0));
+    }
+
+    #[test]
+    fn unmarked_undeveloped_goal() {
+        let mut nodes = nodes();
+        nodes.get_mut("S1").unwrap().supported_by = Some(vec![
+            "G2".to_owned(),
+            "G3".to_owned(),
+            "G4".to_owned(),
+            "G6".to_owned(),
+        ]);
+        nodes.insert("G6".to_owned(), node("main", &[], &[]));
+        let metrics = get_metrics(&nodes, None);
+        assert_eq!(metrics.undeveloped_goals, 100.0 * 2.0 / 3.0);
+    }
+
+    #[test]
+    fn per_module() {
+        let metrics = get_metrics(&nodes(), Some("main"));
+        assert_eq!(metrics.depth, 4);
+        // G3 is a leaf goal within the module.
+        assert_eq!(metrics.undeveloped_goals, 100.0 / 3.0);
+        assert_eq!(metrics.solutions, 2);
+        let metrics = get_metrics(&nodes(), Some("Sub"));
+        assert_eq!(metrics.depth, 3);
+        assert_eq!(metrics.max_branching, 1);
+        assert_eq!(metrics.undeveloped_goals, 0.0);
+        assert!(metrics.evidence_per_subgoal.is_empty());
+        assert_eq!(metrics.get("minEvidencePerSubgoal"), None);
+        assert_eq!(metrics.away_references, 0);
+    }
+
+    #[test]
+    fn thresholds() {
+        let metrics = get_metrics(&nodes(), None);
+        let thresholds: BTreeMap<String, Threshold> = serde_yaml::from_str(
+            "undevelopedGoals: {max: 5}\nsolutions: {min: 1, max: 3}\nminEvidencePerSubgoal: {min: 1}\n",
+        )
+        .unwrap();
+        let mut d = Diagnostics::default();
+        check_thresholds(&mut d, &metrics, &thresholds).unwrap();
+        let messages: Vec<&str> = d.messages.iter().map(|m| m.msg.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "M01: Metric minEvidencePerSubgoal is 0, below the minimum of 1.",
+                "M01: Metric undevelopedGoals is 50, above the maximum of 5.",
+            ]
+        );
+        assert_eq!(d.errors, 2);
+        let thresholds = BTreeMap::from([("undeveloppedGoals".to_owned(), Threshold::default())]);
+        assert_eq!(
+            check_thresholds(&mut d, &metrics, &thresholds)
+                .unwrap_err()
+                .to_string(),
+            "Unknown metric undeveloppedGoals (did you mean undevelopedGoals?)"
+        );
+    }
+}
@@ -1,32 +1,343 @@
+use crate::diagnostics::SourceLocation;
 use crate::dirgraphsvg::edges::{EdgeType, SingleEdge};
+use crate::gsn::confidence::{Combination, Confidence};
 use crate::yaml_fix::MyMap;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, BTreeSet};
+use std::str::FromStr;
 
+pub mod baconian;
 pub mod check;
+pub mod confidence;
+pub mod instantiation;
+pub mod lint;
+pub mod metrics;
+pub mod suggest;
 pub mod validation;
 
+///
+/// Separator between module name and element identifier in qualified references
+/// e.g., `Sub1::G3`.
+///
+pub const MODULE_SEPARATOR: &str = "::";
+
+///
+/// Separator of source and target of a challenged link
+/// e.g., `G1 -> Sn1`.
+///
+pub const CHALLENGED_LINK_SEPARATOR: &str = "->";
+
+///
+/// Attributes whose list items are references to other elements
+///
+pub const SUPPORTED_BY: &str = "supportedBy";
+pub const IN_CONTEXT_OF: &str = "inContextOf";
+
+///
+/// The type of a GSN element
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GsnNodeType {
+    Goal,
+    Strategy,
+    Solution,
+    Context,
+    Assumption,
+    Justification,
+    Module,
+    CounterGoal,
+    CounterSolution,
+}
+
+impl GsnNodeType {
+    ///
+    /// Derive the type of an element from the prefix of its identifier.
+    /// This is the fallback if no explicit `type` is given.
+    ///
+    pub fn from_id(id: &str) -> Option<GsnNodeType> {
+        // Order is important due to Sn and S
+        match get_local_id(id) {
+            id if id.starts_with("Sn") => Some(GsnNodeType::Solution),
+            id if id.starts_with('G') => Some(GsnNodeType::Goal),
+            id if id.starts_with('S') => Some(GsnNodeType::Strategy),
+            id if id.starts_with('C') => Some(GsnNodeType::Context),
+            id if id.starts_with('A') => Some(GsnNodeType::Assumption),
+            id if id.starts_with('J') => Some(GsnNodeType::Justification),
+            _ => None,
+        }
+    }
+}
+
+///
+/// Names of all element types that can be used for `type`
+///
+pub const TYPE_NAMES: [&str; 9] = [
+    "goal",
+    "strategy",
+    "solution",
+    "context",
+    "assumption",
+    "justification",
+    "module",
+    "counterGoal",
+    "counterSolution",
+];
+
+impl FromStr for GsnNodeType {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "goal" => Ok(GsnNodeType::Goal),
+            "strategy" => Ok(GsnNodeType::Strategy),
+            "solution" => Ok(GsnNodeType::Solution),
+            "context" => Ok(GsnNodeType::Context),
+            "assumption" => Ok(GsnNodeType::Assumption),
+            "justification" => Ok(GsnNodeType::Justification),
+            "module" => Ok(GsnNodeType::Module),
+            "countergoal" | "defeater" => Ok(GsnNodeType::CounterGoal),
+            "countersolution" => Ok(GsnNodeType::CounterSolution),
+            _ => Err(()),
+        }
+    }
+}
+
+///
+/// Multiplicity of a link in an argument pattern
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Multiplicity {
+    /// Optional link i.e., `0..1` or `optional`
+    Optional,
+    /// Multiple instances e.g., `n`, `2..n` or `at least 2`
+    Multiple(String),
+}
+
+impl FromStr for Multiplicity {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let is_bound = |b: &str| b == "n" || b == "m" || b.parse::<u32>().is_ok();
+        let s = s.trim();
+        match s {
+            "optional" | "0..1" => Ok(Multiplicity::Optional),
+            s if is_bound(s) => Ok(Multiplicity::Multiple(s.to_owned())),
+            s if s
+                .strip_prefix("at least ")
+                .map(|n| n.trim().parse::<u32>().is_ok())
+                .unwrap_or(false) =>
+            {
+                Ok(Multiplicity::Multiple(s.to_owned()))
+            }
+            s => match s.split_once("..") {
+                Some((lower, upper))
+                    if lower.trim().parse::<u32>().is_ok() && is_bound(upper.trim()) =>
+                {
+                    Ok(Multiplicity::Multiple(s.to_owned()))
+                }
+                _ => Err(()),
+            },
+        }
+    }
+}
+
+impl Multiplicity {
+    ///
+    /// Get the lower and, if limited, the upper bound of the number of instances.
+    /// `n` and `m` allow any number of instances.
+    ///
+    pub fn get_bounds(&self) -> (usize, Option<usize>) {
+        let parse = |b: &str| b.trim().parse::<usize>().ok();
+        match self {
+            Multiplicity::Optional => (0, Some(1)),
+            Multiplicity::Multiple(m) => {
+                if let Some(lower) = m.strip_prefix("at least ") {
+                    (parse(lower).unwrap_or(0), None)
+                } else if let Some((lower, upper)) = m.split_once("..") {
+                    (parse(lower).unwrap_or(0), parse(upper))
+                } else {
+                    parse(m).map(|b| (b, Some(b))).unwrap_or((0, None))
+                }
+            }
+        }
+    }
+}
+
+///
+/// Choice between links in an argument pattern
+///
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Choice {
+    pub(crate) elements: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) label: Option<String>,
+}
+
+impl Choice {
+    ///
+    /// Get the lower and, if limited, the upper bound of the number of alternatives to select.
+    /// The label can be `k of n` or `at least k`; otherwise exactly one alternative is selected.
+    ///
+    pub fn get_bounds(&self) -> (usize, Option<usize>) {
+        let label = self.label.as_deref().unwrap_or_default().trim();
+        if let Some(lower) = label
+            .strip_prefix("at least ")
+            .and_then(|l| l.trim().parse::<usize>().ok())
+        {
+            (lower, None)
+        } else if let Some(count) = label
+            .split_once(" of ")
+            .and_then(|(k, _)| k.trim().parse::<usize>().ok())
+        {
+            (count, Some(count))
+        } else {
+            (1, Some(1))
+        }
+    }
+}
+
 ///
 /// The main struct of this program
 /// It describes a GSN element
 ///
-#[derive(Debug, Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GsnNode {
     pub(crate) text: String,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub(crate) node_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) in_context_of: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) supported_by: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) undeveloped: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) classes: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) sha256: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) level: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) public: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) module_ref: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) uninstantiated: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) multiplicity: Option<MyMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) choice: Option<Choice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) acp: Option<MyMap<String, Option<String>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) challenges: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) confidence: Option<Confidence>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) combination: Option<Combination>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) suppress: Option<Vec<String>>,
     #[serde(flatten)]
-    pub(crate) additional: MyMap<String, String>,
-    #[serde(skip_deserializing)]
+    pub(crate) additional: MyMap<String, serde_yaml::Value>,
+    #[serde(skip)]
     pub(crate) module: String,
+    #[serde(skip)]
+    pub(crate) location: Option<SourceLocation>,
+    #[serde(skip)]
+    pub(crate) reference_locations: BTreeMap<(String, usize), SourceLocation>,
 }
 
 impl GsnNode {
+    ///
+    /// Get the references listed in `attribute` i.e., `supportedBy` or `inContextOf`.
+    ///
+    pub fn get_references(&self, attribute: &str) -> &[String] {
+        match attribute {
+            SUPPORTED_BY => self.supported_by.as_deref(),
+            IN_CONTEXT_OF => self.in_context_of.as_deref(),
+            _ => None,
+        }
+        .unwrap_or_default()
+    }
+
+    ///
+    /// Get the location of the entry at `index` of the reference list `attribute`.
+    /// Falls back to the location of this element if the entry has no location.
+    ///
+    pub fn get_reference_location_at(
+        &self,
+        attribute: &str,
+        index: usize,
+    ) -> Option<&SourceLocation> {
+        self.reference_locations
+            .get(&(attribute.to_owned(), index))
+            .or(self.location.as_ref())
+    }
+
+    ///
+    /// Get the location of the first reference to another element.
+    /// Falls back to the location of this element if the reference has no location.
+    ///
+    pub fn get_reference_location(&self, reference: &str) -> Option<&SourceLocation> {
+        [SUPPORTED_BY, IN_CONTEXT_OF]
+            .into_iter()
+            .find_map(|attribute| {
+                self.get_references(attribute)
+                    .iter()
+                    .position(|r| r == reference)
+                    .map(|index| self.get_reference_location_at(attribute, index))
+            })
+            .unwrap_or(self.location.as_ref())
+    }
+
+    ///
+    /// Get the type of the element.
+    /// An explicit `type` attribute takes precedence over the prefix of the identifier.
+    /// None is returned if the type is unknown.
+    ///
+    pub fn get_type(&self, id: &str) -> Option<GsnNodeType> {
+        match &self.node_type {
+            Some(t) => GsnNodeType::from_str(t).ok(),
+            None => GsnNodeType::from_id(id),
+        }
+    }
+
+    ///
+    /// Get the module that is represented by this element.
+    /// For module references, this is the referenced module.
+    ///
+    pub fn get_target_module(&self, id: &str) -> &String {
+        match (self.get_type(id), &self.module_ref) {
+            (Some(GsnNodeType::Module), Some(module_ref)) => module_ref,
+            _ => &self.module,
+        }
+    }
+
+    ///
+    /// Check if the element uses the pattern extension
+    /// i.e., it is uninstantiated or has multiplicities or choices.
+    ///
+    pub fn is_pattern_element(&self) -> bool {
+        self.uninstantiated == Some(true) || self.multiplicity.is_some() || self.choice.is_some()
+    }
+
+    ///
+    /// Get the element challenged by this element.
+    /// If a link is challenged i.e., `A -> B`, the target of the link is returned as well.
+    ///
+    pub fn get_challenged(&self) -> Option<(&str, Option<&str>)> {
+        self.challenges
+            .as_ref()
+            .map(|c| match c.split_once(CHALLENGED_LINK_SEPARATOR) {
+                Some((source, target)) => (source.trim(), Some(target.trim())),
+                None => (c.trim(), None),
+            })
+    }
+
     pub fn get_edges(&self) -> Vec<(String, EdgeType)> {
         let mut edges = Vec::new();
         if let Some(c_nodes) = &self.in_context_of {
@@ -47,19 +358,26 @@ impl GsnNode {
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ModuleInformation {
     pub(crate) name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) brief: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) pattern: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) confidence_for: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) levels: Option<Vec<String>>,
     #[serde(flatten)]
-    pub(crate) additional: MyMap<String, String>,
+    pub(crate) additional: MyMap<String, serde_yaml::Value>,
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(untagged)]
 pub enum GsnDocumentNode {
-    GsnNode(GsnNode),
+    GsnNode(Box<GsnNode>),
     ModuleInformation(ModuleInformation),
 }
 
@@ -68,13 +386,187 @@ pub struct Module {
     pub meta: Option<ModuleInformation>,
 }
 
+///
+/// Get the identifier of an element within its module
+/// i.e., strip the module name from a qualified identifier.
+///
+pub fn get_local_id(id: &str) -> &str {
+    id.rsplit_once(MODULE_SEPARATOR)
+        .map(|(_, local)| local)
+        .unwrap_or(id)
+}
+
+///
+/// Get the qualified identifier of element `id` in `module` e.g., `Sub1::G3`.
+///
+pub fn get_qualified_id(module: &str, id: &str) -> String {
+    format!("{}{}{}", module, MODULE_SEPARATOR, id)
+}
+
+///
+/// Get the identifier of an element as it is shown for `module`
+/// i.e., the module name is omitted for elements of the same module.
+///
+pub fn get_display_id<'a>(id: &'a str, module: &str) -> &'a str {
+    id.strip_prefix(module)
+        .and_then(|local| local.strip_prefix(MODULE_SEPARATOR))
+        .unwrap_or(id)
+}
+
+///
+/// Get the module of all elements, if they belong to the same module, otherwise an empty string.
+/// Output that is not specific to a module shows identifiers relative to this module.
+///
+pub fn get_single_module(nodes: &MyMap<String, GsnNode>) -> &str {
+    let mut modules = nodes.values().map(|n| n.module.as_str());
+    let first = modules.next().unwrap_or_default();
+    if modules.all(|m| m == first) {
+        first
+    } else {
+        ""
+    }
+}
+
+///
+/// Get the diagnostic codes that are suppressed per element.
+///
+pub fn get_suppressions(nodes: &MyMap<String, GsnNode>) -> BTreeMap<String, Vec<String>> {
+    nodes
+        .iter()
+        .filter_map(|(id, node)| Some((id.to_owned(), node.suppress.to_owned()?)))
+        .collect()
+}
+
+///
+/// Merge the elements of all modules into one map.
+///
+/// Identifiers only need to be unique within a module,
+/// thus all elements are keyed by their qualified identifier e.g., `Sub1::G3`.
+/// Afterwards, all references are resolved to the keys of the merged map.
+///
+pub fn merge_modules(module_nodes: Vec<(String, GsnNode)>) -> MyMap<String, GsnNode> {
+    let mut nodes = MyMap::<String, GsnNode>::new();
+    for (id, node) in module_nodes {
+        nodes.insert(get_qualified_id(&node.module, &id), node);
+    }
+    let mut local_ids = BTreeMap::<String, Vec<String>>::new();
+    for key in nodes.keys() {
+        local_ids
+            .entry(get_local_id(key).to_owned())
+            .or_default()
+            .push(key.to_owned());
+    }
+    // Resolving only depends on the keys and modules, thus references can be replaced in place.
+    let keys: Vec<String> = nodes.keys().cloned().collect();
+    for key in keys {
+        let node = nodes.get(&key).unwrap();
+        let module = node.module.to_owned();
+        let resolve = |refs: &Option<Vec<String>>| -> Option<Vec<String>> {
+            refs.as_ref().map(|refs| {
+                refs.iter()
+                    .map(|r| resolve_reference(&nodes, &local_ids, &module, r))
+                    .collect()
+            })
+        };
+        let in_context_of = resolve(&node.in_context_of);
+        let supported_by = resolve(&node.supported_by);
+        let multiplicity = node.multiplicity.as_ref().map(|m| {
+            let mut resolved = MyMap::new();
+            for (r, v) in m.iter() {
+                resolved.insert(
+                    resolve_reference(&nodes, &local_ids, &module, r),
+                    v.to_owned(),
+                );
+            }
+            resolved
+        });
+        let acp = node.acp.as_ref().map(|a| {
+            let mut resolved = MyMap::new();
+            for (name, target) in a.iter() {
+                let target = target
+                    .as_ref()
+                    .map(|t| resolve_reference(&nodes, &local_ids, &module, t));
+                resolved.insert(name.to_owned(), target);
+            }
+            resolved
+        });
+        let challenges = node.get_challenged().map(|(source, target)| {
+            let source = resolve_reference(&nodes, &local_ids, &module, source);
+            match target {
+                Some(target) => format!(
+                    "{} {} {}",
+                    source,
+                    CHALLENGED_LINK_SEPARATOR,
+                    resolve_reference(&nodes, &local_ids, &module, target)
+                ),
+                None => source,
+            }
+        });
+        let combination = match &node.combination {
+            Some(Combination::Weighted(w)) => {
+                let mut resolved = MyMap::new();
+                for (r, v) in w.iter() {
+                    resolved.insert(resolve_reference(&nodes, &local_ids, &module, r), *v);
+                }
+                Some(Combination::Weighted(resolved))
+            }
+            c => c.to_owned(),
+        };
+        let choice = node.choice.as_ref().map(|c| Choice {
+            elements: resolve(&Some(c.elements.to_owned())).unwrap_or_default(),
+            label: c.label.to_owned(),
+        });
+        let node = nodes.get_mut(&key).unwrap();
+        node.in_context_of = in_context_of;
+        node.supported_by = supported_by;
+        node.multiplicity = multiplicity;
+        node.choice = choice;
+        node.acp = acp;
+        node.challenges = challenges;
+        node.combination = combination;
+    }
+    nodes
+}
+
+///
+/// Resolve a reference from within `module` to a key of the merged map.
+///
+/// An unqualified reference is first looked up in the same module,
+/// then in all modules, if only one of them has an element with this identifier.
+/// A qualified reference is looked up in the given module only.
+/// References that cannot be resolved are returned unchanged.
+///
+fn resolve_reference(
+    nodes: &MyMap<String, GsnNode>,
+    local_ids: &BTreeMap<String, Vec<String>>,
+    module: &str,
+    reference: &str,
+) -> String {
+    if reference.contains(MODULE_SEPARATOR) {
+        return reference.to_owned();
+    }
+    let key = get_qualified_id(module, reference);
+    if nodes.contains_key(&key) {
+        return key;
+    }
+    match local_ids.get(reference).map(|keys| keys.as_slice()) {
+        Some([key]) => key.to_owned(),
+        _ => reference.to_owned(),
+    }
+}
+
 ///
 /// Get root nodes
 /// These are the unreferenced nodes.
+/// Elements challenging other elements are attached to them and are thus no root nodes.
 ///
 fn get_root_nodes(nodes: &MyMap<String, GsnNode>) -> Vec<String> {
     // Usage of BTreeSet, since root nodes might be used in output and that should be deterministic
-    let mut root_nodes: BTreeSet<String> = nodes.keys().cloned().collect();
+    let mut root_nodes: BTreeSet<String> = nodes
+        .iter()
+        .filter(|(_, n)| n.challenges.is_none())
+        .map(|(id, _)| id.to_owned())
+        .collect();
     for node in nodes.values() {
         // Remove all keys if they are referenced; used to see if there is more than one top level node
         if let Some(context) = node.in_context_of.as_ref() {
@@ -91,6 +583,75 @@ fn get_root_nodes(nodes: &MyMap<String, GsnNode>) -> Vec<String> {
     Vec::from_iter(root_nodes)
 }
 
+///
+/// Get the elements challenging each element or a link starting at it.
+///
+pub fn get_challengers(nodes: &MyMap<String, GsnNode>) -> BTreeMap<&str, Vec<&String>> {
+    let mut challengers = BTreeMap::<&str, Vec<&String>>::new();
+    for (id, node) in nodes.iter() {
+        if let Some((challenged, _)) = node.get_challenged() {
+            challengers.entry(challenged).or_default().push(id);
+        }
+    }
+    challengers
+}
+
+///
+/// Check if an element is rebutted i.e., challenged by an element that is not rebutted itself.
+/// The `challengers` are the ones returned by `get_challengers`.
+///
+pub fn is_rebutted(challengers: &BTreeMap<&str, Vec<&String>>, id: &str) -> bool {
+    fn rebutted(
+        challengers: &BTreeMap<&str, Vec<&String>>,
+        id: &str,
+        visiting: &mut BTreeSet<String>,
+    ) -> bool {
+        // Elements challenging each other in a cycle do not rebut each other.
+        if !visiting.insert(id.to_owned()) {
+            return false;
+        }
+        let rebutted = challengers
+            .get(id)
+            .into_iter()
+            .flatten()
+            .any(|c| !rebutted(challengers, c, visiting));
+        visiting.remove(id);
+        rebutted
+    }
+    rebutted(challengers, id, &mut BTreeSet::new())
+}
+
+///
+/// Get all unresolved defeaters.
+///
+/// A defeater is an element challenging another element or link.
+/// It is resolved if it is rebutted (see `is_rebutted`), supported by further elements or a counter solution.
+///
+pub fn get_unresolved_defeaters(nodes: &MyMap<String, GsnNode>) -> Vec<&String> {
+    let challengers = get_challengers(nodes);
+    nodes
+        .iter()
+        .filter(|(id, n)| {
+            n.challenges.is_some()
+                && n.supported_by.is_none()
+                && n.get_type(id) != Some(GsnNodeType::CounterSolution)
+                && !is_rebutted(&challengers, id)
+        })
+        .map(|(id, _)| id)
+        .collect()
+}
+
+///
+/// Get the type of a referenced element.
+/// If the element is not known, the type is derived from the identifier.
+///
+pub fn get_node_type(nodes: &MyMap<String, GsnNode>, id: &str) -> Option<GsnNodeType> {
+    nodes
+        .get(id)
+        .map(|n| n.get_type(id))
+        .unwrap_or_else(|| GsnNodeType::from_id(id))
+}
+
 ///
 /// Gathers all different 'level' attributes from all nodes.
 ///
@@ -143,7 +704,7 @@ fn add_dependencies(
     dep_type: SingleEdge,
 ) {
     for child in children {
-        let other_module = &nodes.get(child).unwrap().module;
+        let other_module = nodes.get(child).unwrap().get_target_module(child);
         if &cur_node.module != other_module {
             let oneway = dependencies
                 .get(&cur_node.module)
@@ -225,4 +786,131 @@ mod test {
         assert!(output.contains_key(&"x1"));
         assert!(output.contains_key(&"x2"));
     }
+
+    fn module_node(module: &str, supported_by: Option<Vec<&str>>) -> GsnNode {
+        GsnNode {
+            supported_by: supported_by.map(|s| s.iter().map(|&x| x.to_owned()).collect()),
+            module: module.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn merge_unique_ids() {
+        let nodes = merge_modules(vec![
+            ("G1".to_owned(), module_node("Main", Some(vec!["G2"]))),
+            ("G2".to_owned(), module_node("Sub1", None)),
+        ]);
+        assert_eq!(
+            nodes.keys().cloned().collect::<Vec<String>>(),
+            vec!["Main::G1", "Sub1::G2"]
+        );
+        assert_eq!(
+            nodes.get("Main::G1").unwrap().supported_by,
+            Some(vec!["Sub1::G2".to_owned()])
+        );
+    }
+
+    #[test]
+    fn merge_duplicate_ids() {
+        let nodes = merge_modules(vec![
+            (
+                "G1".to_owned(),
+                module_node("Main", Some(vec!["G2", "Sub1::G1"])),
+            ),
+            ("G2".to_owned(), module_node("Main", None)),
+            ("G1".to_owned(), module_node("Sub1", Some(vec!["Sn1"]))),
+            ("Sn1".to_owned(), module_node("Sub1", None)),
+            (
+                "G3".to_owned(),
+                module_node("Sub2", Some(vec!["G1", "G2", "Sub1::Sn1", "Sub2::Sn1"])),
+            ),
+        ]);
+        assert_eq!(
+            nodes.keys().cloned().collect::<Vec<String>>(),
+            vec!["Main::G1", "Main::G2", "Sub1::G1", "Sub1::Sn1", "Sub2::G3"]
+        );
+        assert_eq!(
+            nodes.get("Main::G1").unwrap().supported_by,
+            Some(vec!["Main::G2".to_owned(), "Sub1::G1".to_owned()])
+        );
+        assert_eq!(
+            nodes.get("Sub1::G1").unwrap().supported_by,
+            Some(vec!["Sub1::Sn1".to_owned()])
+        );
+        // Ambiguous and wrongly qualified references stay unresolved
+        assert_eq!(
+            nodes.get("Sub2::G3").unwrap().supported_by,
+            Some(vec![
+                "G1".to_owned(),
+                "Main::G2".to_owned(),
+                "Sub1::Sn1".to_owned(),
+                "Sub2::Sn1".to_owned()
+            ])
+        );
+    }
+
+    #[test]
+    fn display_id() {
+        assert_eq!(get_display_id("Main::G1", "Main"), "G1");
+        assert_eq!(get_display_id("Sub1::G1", "Main"), "Sub1::G1");
+        assert_eq!(get_display_id("Main::G1", "Mai"), "Main::G1");
+        assert_eq!(get_display_id("G1", ""), "G1");
+    }
+
+    #[test]
+    fn local_id() {
+        assert_eq!(get_local_id("G1"), "G1");
+        assert_eq!(get_local_id("Sub1::G1"), "G1");
+        assert_eq!(GsnNodeType::from_id("Sub1::G1"), Some(GsnNodeType::Goal));
+    }
+
+    #[test]
+    fn multiplicity() {
+        assert_eq!(Multiplicity::from_str("0..1"), Ok(Multiplicity::Optional));
+        assert_eq!(
+            Multiplicity::from_str("optional"),
+            Ok(Multiplicity::Optional)
+        );
+        assert_eq!(
+            Multiplicity::from_str("n"),
+            Ok(Multiplicity::Multiple("n".to_owned()))
+        );
+        assert_eq!(
+            Multiplicity::from_str(" 2..n "),
+            Ok(Multiplicity::Multiple("2..n".to_owned()))
+        );
+        assert_eq!(
+            Multiplicity::from_str("at least 3"),
+            Ok(Multiplicity::Multiple("at least 3".to_owned()))
+        );
+        assert_eq!(Multiplicity::from_str("at least n"), Err(()));
+        assert_eq!(Multiplicity::from_str("some"), Err(()));
+    }
+
+    #[test]
+    fn multiplicity_bounds() {
+        let bounds = |m: &str| Multiplicity::from_str(m).unwrap().get_bounds();
+        assert_eq!(bounds("optional"), (0, Some(1)));
+        assert_eq!(bounds("n"), (0, None));
+        assert_eq!(bounds("2"), (2, Some(2)));
+        assert_eq!(bounds("2..n"), (2, None));
+        assert_eq!(bounds("2..5"), (2, Some(5)));
+        assert_eq!(bounds("at least 3"), (3, None));
+    }
+
+    #[test]
+    fn choice_bounds() {
+        let bounds = |label: Option<&str>| {
+            Choice {
+                elements: vec!["G1".to_owned(), "G2".to_owned(), "G3".to_owned()],
+                label: label.map(|l| l.to_owned()),
+            }
+            .get_bounds()
+        };
+        assert_eq!(bounds(None), (1, Some(1)));
+        assert_eq!(bounds(Some("2 of 3")), (2, Some(2)));
+        assert_eq!(bounds(Some("at least 1")), (1, None));
+        assert_eq!(bounds(Some("any")), (1, Some(1)));
+    }
 }
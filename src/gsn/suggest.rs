@@ -0,0 +1,110 @@
+use super::get_local_id;
+
+///
+/// Edit distance as optimal string alignment
+///
+/// An edit is the insertion, deletion or substitution of a character
+/// or the transposition of two adjacent characters e.g., `Sn12` and `Sn21`.
+///
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+///
+/// Get the candidates closest to `name`, at most three.
+///
+/// Module names of qualified identifiers are ignored for the comparison.
+/// Candidates that only differ in case are preferred.
+/// One edit is allowed per three characters, thus short names only match if they differ in case.
+///
+pub fn get_suggestions<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Vec<&'a str> {
+    let local_name = get_local_id(name);
+    let max_distance = local_name.chars().count() / 3;
+    let mut scored: Vec<((usize, usize), &str)> = candidates
+        .into_iter()
+        .filter(|c| *c != name)
+        .map(|c| {
+            let local = get_local_id(c);
+            let score = (
+                edit_distance(&local.to_lowercase(), &local_name.to_lowercase()),
+                edit_distance(local, local_name),
+            );
+            (score, c)
+        })
+        .filter(|((distance, _), _)| *distance <= max_distance)
+        .collect();
+    scored.sort();
+    scored.into_iter().take(3).map(|(_, c)| c).collect()
+}
+
+///
+/// Format suggestions to be appended to a message.
+/// The result is empty if there are no suggestions.
+///
+pub fn format_suggestions(suggestions: &[&str]) -> String {
+    match suggestions {
+        [] => String::new(),
+        [one] => format!(" (did you mean {}?)", one),
+        [init @ .., last] => format!(" (did you mean {} or {}?)", init.join(", "), last),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn distances() {
+        assert_eq!(edit_distance("Sn12", "Sn21"), 1);
+        assert_eq!(edit_distance("G3a", "G3A"), 1);
+        assert_eq!(edit_distance("G1", "G1"), 0);
+        assert_eq!(edit_distance("", "G1"), 2);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn suggestions() {
+        let candidates = ["G3A", "G3", "Sn21", "Sn1", "Sub::G3a", "C1"];
+        assert_eq!(
+            get_suggestions("G3a", candidates),
+            vec!["Sub::G3a", "G3A", "G3"]
+        );
+        assert_eq!(get_suggestions("Sn12", candidates), vec!["Sn1", "Sn21"]);
+        assert!(get_suggestions("Justification", candidates).is_empty());
+        assert_eq!(get_suggestions("c1", candidates), vec!["C1"]);
+        assert!(get_suggestions("G1", candidates).is_empty());
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(format_suggestions(&[]), "");
+        assert_eq!(format_suggestions(&["G1"]), " (did you mean G1?)");
+        assert_eq!(
+            format_suggestions(&["G1", "G2", "G3"]),
+            " (did you mean G1, G2 or G3?)"
+        );
+    }
+}
@@ -1,7 +1,13 @@
-use super::GsnNode;
-use crate::diagnostics::{DiagType, Diagnostics};
+use super::confidence::Combination;
+use super::suggest::{format_suggestions, get_suggestions};
+use super::{
+    get_display_id, get_local_id, get_node_type, GsnNode, GsnNodeType, Module, Multiplicity,
+    IN_CONTEXT_OF, SUPPORTED_BY, TYPE_NAMES,
+};
+use crate::diagnostics::{DiagType, Diagnostics, SourceLocation};
 use crate::yaml_fix::MyMap;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
 
 ///
 /// Validate all ids and nodes
@@ -9,10 +15,42 @@ use std::collections::HashSet;
 ///
 pub fn validate_module(diag: &mut Diagnostics, module: &str, nodes: &MyMap<String, GsnNode>) {
     for (id, node) in nodes.iter().filter(|(_, n)| n.module == module) {
-        // Validate if key is one of the known prefixes
-        validate_id(diag, module, id);
+        // Validate if type is known, either explicitly or by one of the known prefixes
+        validate_type(diag, module, id, node);
         // Validate all references of node
-        validate_references(diag, module, id, node);
+        validate_references(diag, module, id, node, nodes);
+        // Validate the challenged element or link
+        validate_challenges(diag, module, id, node, nodes);
+        // Validate confidences and their combination
+        validate_confidence(diag, module, id, node);
+    }
+}
+
+///
+/// Validate type
+///
+/// Check if an explicit type is a known type
+/// or if node id starts with a know prefix otherwise.
+///
+fn validate_type(diag: &mut Diagnostics, module: &str, id: &str, node: &GsnNode) {
+    match &node.node_type {
+        Some(t) if node.get_type(id).is_none() => {
+            diag.add_located_msg(
+                DiagType::Error,
+                Some(module),
+                Some(id),
+                node.location.as_ref(),
+                Vec::new(),
+                format!(
+                    "V01: Element {} has unknown type {}{}. Please see README for supported types",
+                    get_display_id(id, module),
+                    t,
+                    format_suggestions(&get_suggestions(t, TYPE_NAMES))
+                ),
+            );
+        }
+        Some(_) => (),
+        None => validate_id(diag, module, id, node.location.as_ref()),
     }
 }
 
@@ -21,21 +59,30 @@ pub fn validate_module(diag: &mut Diagnostics, module: &str, nodes: &MyMap<Strin
 ///
 /// Check if node id starts with a know prefix
 ///
-fn validate_id(diag: &mut Diagnostics, module: &str, id: &str) {
-    // Order is important due to Sn and S
-    if !(id.starts_with("Sn")
-        || id.starts_with('G')
-        || id.starts_with('A')
-        || id.starts_with('J')
-        || id.starts_with('S')
-        || id.starts_with('C'))
-    {
-        diag.add_msg(
+fn validate_id(diag: &mut Diagnostics, module: &str, id: &str, location: Option<&SourceLocation>) {
+    if GsnNodeType::from_id(id).is_none() {
+        // Suggest the identifier with an upper case prefix e.g., `Sn1` for `sn1`.
+        let mut chars = get_local_id(id).chars();
+        let capitalized: String = chars
+            .next()
+            .map(|c| c.to_ascii_uppercase())
+            .into_iter()
+            .chain(chars)
+            .collect();
+        let suggestions = match GsnNodeType::from_id(&capitalized) {
+            Some(_) => vec![capitalized.as_str()],
+            None => vec![],
+        };
+        diag.add_located_msg(
             DiagType::Error,
             Some(module),
+            Some(id),
+            location,
+            Vec::new(),
             format!(
-                "V01: Elememt {} is of unknown type. Please see README for supported types",
-                id
+                "V01: Elememt {} is of unknown type{}. Please see README for supported types",
+                get_display_id(id, module),
+                format_suggestions(&suggestions)
             ),
         );
     }
@@ -48,40 +95,452 @@ fn validate_id(diag: &mut Diagnostics, module: &str, id: &str) {
 /// - Check supported_by references for wellformedness
 /// - Check if undeveloped is correctly set
 ///
-fn validate_references(diag: &mut Diagnostics, module: &str, id: &str, node: &GsnNode) {
-    if let Some(in_context) = node.in_context_of.as_ref() {
+fn validate_references(
+    diag: &mut Diagnostics,
+    module: &str,
+    id: &str,
+    node: &GsnNode,
+    nodes: &MyMap<String, GsnNode>,
+) {
+    let node_type = node.get_type(id);
+    // Goals, strategies, solutions and counter goals can have references
+    let can_reference = matches!(
+        node_type,
+        Some(GsnNodeType::Goal)
+            | Some(GsnNodeType::Strategy)
+            | Some(GsnNodeType::Solution)
+            | Some(GsnNodeType::CounterGoal)
+    );
+    if node.in_context_of.is_some() {
         let mut valid_refs = vec![];
-        // Only goals and strategies can have contexts, assumptions and goals
-        if id.starts_with('S') || id.starts_with('G') {
-            valid_refs.append(&mut vec!["J", "A", "C"]);
+        if can_reference {
+            valid_refs.append(&mut vec![
+                GsnNodeType::Justification,
+                GsnNodeType::Assumption,
+                GsnNodeType::Context,
+            ]);
         }
-        validate_reference(diag, module, id, in_context, "context", &valid_refs);
+        validate_reference(diag, id, node, IN_CONTEXT_OF, "context", &valid_refs, nodes);
     }
-    if let Some(support) = node.supported_by.as_ref() {
+    if node.supported_by.is_some() {
         let mut valid_refs = vec![];
-        // Only goals and strategies can have other goals, strategies and solutions
-        if id.starts_with('S') || id.starts_with('G') {
-            valid_refs.append(&mut vec!["G", "Sn", "S"]);
+        if node_type == Some(GsnNodeType::CounterGoal) {
+            // Counter goals are supported by a counter argument
+            valid_refs.append(&mut vec![
+                GsnNodeType::Goal,
+                GsnNodeType::Solution,
+                GsnNodeType::Strategy,
+                GsnNodeType::CounterGoal,
+                GsnNodeType::CounterSolution,
+            ]);
+        } else if can_reference {
+            valid_refs.append(&mut vec![
+                GsnNodeType::Goal,
+                GsnNodeType::Solution,
+                GsnNodeType::Strategy,
+                GsnNodeType::Module,
+            ]);
         }
         validate_reference(
             diag,
-            module,
             id,
-            support,
+            node,
+            SUPPORTED_BY,
             "supported by element",
             &valid_refs,
+            nodes,
         );
         if Some(true) == node.undeveloped {
-            diag.add_error(
+            diag.add_error_at(
                 Some(module),
-                format!("V03: Undeveloped element {} has supporting arguments.", id),
+                Some(id),
+                node.location.as_ref(),
+                format!(
+                    "V03: Undeveloped element {} has supporting arguments.",
+                    get_display_id(id, module)
+                ),
             );
         }
-    } else if (id.starts_with('S') && !id.starts_with("Sn") || id.starts_with('G'))
-        && (Some(false) == node.undeveloped || node.undeveloped.is_none())
+    } else if matches!(
+        node_type,
+        Some(GsnNodeType::Goal) | Some(GsnNodeType::Strategy)
+    ) && (Some(false) == node.undeveloped || node.undeveloped.is_none())
     {
         // No "supported by" entries, but Strategy and Goal => undeveloped
-        diag.add_warning(Some(module), format!("V02: Element {} is undeveloped.", id));
+        diag.add_warning_at(
+            Some(module),
+            Some(id),
+            node.location.as_ref(),
+            format!(
+                "V02: Element {} is undeveloped.",
+                get_display_id(id, module)
+            ),
+        );
+    }
+}
+
+///
+/// Validate challenges of the dialectic extension
+///
+/// Check that only counter goals and counter solutions challenge other elements
+/// and that a challenged link exists.
+///
+fn validate_challenges(
+    diag: &mut Diagnostics,
+    module: &str,
+    id: &str,
+    node: &GsnNode,
+    nodes: &MyMap<String, GsnNode>,
+) {
+    if let Some((challenged, link_target)) = node.get_challenged() {
+        if !matches!(
+            node.get_type(id),
+            Some(GsnNodeType::CounterGoal) | Some(GsnNodeType::CounterSolution)
+        ) {
+            diag.add_error_at(
+                Some(module),
+                Some(id),
+                node.location.as_ref(),
+                format!(
+                    "V12: Element {} challenges {}, but only counter goals and counter solutions can challenge.",
+                    get_display_id(id, module), get_display_id(challenged, module)
+                ),
+            );
+        }
+        if let (Some(link_target), Some(source)) = (link_target, nodes.get(challenged)) {
+            if !source.get_edges().iter().any(|(t, _)| t == link_target) {
+                diag.add_error_at(
+                    Some(module),
+                    Some(id),
+                    node.location.as_ref(),
+                    format!(
+                        "V12: Element {} challenges the link from {} to {}, which does not exist.",
+                        get_display_id(id, module),
+                        get_display_id(challenged, module),
+                        get_display_id(link_target, module)
+                    ),
+                );
+            }
+        }
+    }
+}
+
+///
+/// Validate confidences
+///
+/// Check that only solutions have a well-formed confidence
+/// and only strategies declare how confidences are combined.
+///
+fn validate_confidence(diag: &mut Diagnostics, module: &str, id: &str, node: &GsnNode) {
+    let node_type = node.get_type(id);
+    if let Some(confidence) = &node.confidence {
+        if node_type != Some(GsnNodeType::Solution) {
+            diag.add_error_at(
+                Some(module),
+                Some(id),
+                node.location.as_ref(),
+                format!(
+                    "V13: Element {} has a confidence, but is not a solution.",
+                    get_display_id(id, module)
+                ),
+            );
+        } else if !confidence.is_valid() {
+            diag.add_error_at(
+                Some(module),
+                Some(id),
+                node.location.as_ref(),
+                format!(
+                    "V13: Confidence of element {} must be between 0 and 1 and an opinion must sum up to 1.",
+                    get_display_id(id, module)
+                ),
+            );
+        }
+    }
+    if let Some(combination) = &node.combination {
+        if node_type != Some(GsnNodeType::Strategy) {
+            diag.add_error_at(
+                Some(module),
+                Some(id),
+                node.location.as_ref(),
+                format!(
+                    "V13: Element {} has a combination, but is not a strategy.",
+                    get_display_id(id, module)
+                ),
+            );
+        }
+        if let Combination::Weighted(weights) = combination {
+            for (target, weight) in weights.iter() {
+                if !node.supported_by.iter().flatten().any(|s| s == target) {
+                    diag.add_error_at(
+                        Some(module),
+                        Some(id),
+                        node.location.as_ref(),
+                        format!(
+                            "V13: Weight of element {} refers to {} which is not in supportedBy.",
+                            get_display_id(id, module),
+                            get_display_id(target, module)
+                        ),
+                    );
+                }
+                if *weight < 0.0 {
+                    diag.add_error_at(
+                        Some(module),
+                        Some(id),
+                        node.location.as_ref(),
+                        format!(
+                            "V13: Weight of element {} for {} is negative.",
+                            get_display_id(id, module),
+                            get_display_id(target, module)
+                        ),
+                    );
+                }
+            }
+        }
+    }
+}
+
+///
+/// Validate module references
+///
+/// Check if module references refer to a module that exists.
+///
+pub fn validate_module_references(
+    diag: &mut Diagnostics,
+    module: &str,
+    nodes: &MyMap<String, GsnNode>,
+    modules: &HashMap<String, Module>,
+) {
+    for (id, node) in nodes
+        .iter()
+        .filter(|(id, n)| n.module == module && n.get_type(id) == Some(GsnNodeType::Module))
+    {
+        match &node.module_ref {
+            Some(module_ref) if !modules.contains_key(module_ref) => diag.add_error_at(
+                Some(module),
+                Some(id),
+                node.location.as_ref(),
+                format!(
+                    "V07: Module reference {} refers to unknown module {}.",
+                    get_display_id(id, module),
+                    module_ref
+                ),
+            ),
+            Some(_) => (),
+            None => diag.add_error_at(
+                Some(module),
+                Some(id),
+                node.location.as_ref(),
+                format!(
+                    "V07: Module reference {} does not refer to a module.",
+                    get_display_id(id, module)
+                ),
+            ),
+        }
+    }
+}
+
+///
+/// Validate pattern annotations
+///
+/// - Check that multiplicities and choices refer to linked elements
+/// - Check that multiplicities are well-formed
+/// - Check that choices have at least two elements
+/// - Check that only patterns contain elements of the pattern extension
+///
+pub fn validate_pattern(
+    diag: &mut Diagnostics,
+    module: &str,
+    nodes: &MyMap<String, GsnNode>,
+    modules: &HashMap<String, Module>,
+) {
+    let is_pattern = modules
+        .get(module)
+        .and_then(|m| m.meta.as_ref())
+        .and_then(|m| m.pattern)
+        .unwrap_or(false);
+    for (id, node) in nodes.iter().filter(|(_, n)| n.module == module) {
+        let is_linked = |target: &String| {
+            node.supported_by.iter().flatten().any(|r| r == target)
+                || node.in_context_of.iter().flatten().any(|r| r == target)
+        };
+        for (target, multiplicity) in node.multiplicity.iter().flat_map(|m| m.iter()) {
+            if !is_linked(target) {
+                diag.add_error_at(
+                    Some(module),
+                    Some(id),
+                    node.location.as_ref(),
+                    format!(
+                        "V08: Multiplicity of element {} refers to {} which is neither in supportedBy nor inContextOf.",
+                        get_display_id(id, module), get_display_id(target, module)
+                    ),
+                );
+            }
+            if Multiplicity::from_str(multiplicity).is_err() {
+                diag.add_error_at(
+                    Some(module),
+                    Some(id),
+                    node.location.as_ref(),
+                    format!(
+                        "V08: Element {} has invalid multiplicity {} for {}.",
+                        get_display_id(id, module),
+                        multiplicity,
+                        get_display_id(target, module)
+                    ),
+                );
+            }
+        }
+        if let Some(choice) = &node.choice {
+            for target in choice.elements.iter().filter(|t| !is_linked(t)) {
+                diag.add_error_at(
+                    Some(module),
+                    Some(id),
+                    node.location.as_ref(),
+                    format!(
+                        "V08: Choice of element {} refers to {} which is neither in supportedBy nor inContextOf.",
+                        get_display_id(id, module), get_display_id(target, module)
+                    ),
+                );
+            }
+            if choice.elements.len() < 2 {
+                diag.add_error_at(
+                    Some(module),
+                    Some(id),
+                    node.location.as_ref(),
+                    format!(
+                        "V08: Choice of element {} must have at least two elements.",
+                        get_display_id(id, module)
+                    ),
+                );
+            }
+        }
+        if !is_pattern && node.is_pattern_element() {
+            diag.add_warning_at(
+                Some(module),
+                Some(id),
+                node.location.as_ref(),
+                format!(
+                    "V09: Element {} uses the pattern extension, but module {} is not a pattern.",
+                    get_display_id(id, module),
+                    module
+                ),
+            );
+        }
+    }
+}
+
+///
+/// Validate assurance claim points
+///
+/// - Check that ACPs on links refer to linked elements
+/// - Check that ACP names are unique
+/// - Check that confidence arguments attach to a known ACP
+///
+pub fn validate_acps(
+    diag: &mut Diagnostics,
+    module: &str,
+    nodes: &MyMap<String, GsnNode>,
+    modules: &HashMap<String, Module>,
+) {
+    let mut acps = HashMap::<&str, &str>::new();
+    for (id, node) in nodes.iter() {
+        for (name, target) in node.acp.iter().flat_map(|a| a.iter()) {
+            let first = *acps.entry(name.as_str()).or_insert(id.as_str());
+            if node.module != module {
+                continue;
+            }
+            if first != id {
+                diag.add_error_at(
+                    Some(module),
+                    Some(id),
+                    node.location.as_ref(),
+                    format!(
+                        "V10: ACP {} of element {} is already defined for element {}.",
+                        name,
+                        get_display_id(id, module),
+                        get_display_id(first, module)
+                    ),
+                );
+            }
+            if let Some(target) = target {
+                if !node.get_edges().iter().any(|(t, _)| t == target) {
+                    diag.add_error_at(
+                        Some(module),
+                        Some(id),
+                        node.location.as_ref(),
+                        format!(
+                            "V10: ACP {} of element {} refers to {} which is neither in supportedBy nor inContextOf.",
+                            name, get_display_id(id, module), get_display_id(target, module)
+                        ),
+                    );
+                }
+            }
+        }
+    }
+    if let Some(acp) = modules
+        .get(module)
+        .and_then(|m| m.meta.as_ref())
+        .and_then(|m| m.confidence_for.as_ref())
+    {
+        if !acps.contains_key(acp.as_str()) {
+            diag.add_error(
+                Some(module),
+                format!(
+                    "V11: Module {} is a confidence argument for unknown ACP {}.",
+                    module, acp
+                ),
+            );
+        }
+    }
+}
+
+///
+/// Validate levels against the ordered list of levels in the module information.
+///
+/// - Check that the declared levels are unique
+/// - Check that elements only use declared levels
+///
+pub fn validate_levels(
+    diag: &mut Diagnostics,
+    module: &str,
+    nodes: &MyMap<String, GsnNode>,
+    modules: &HashMap<String, Module>,
+) {
+    let levels = match modules
+        .get(module)
+        .and_then(|m| m.meta.as_ref())
+        .and_then(|m| m.levels.as_ref())
+    {
+        Some(levels) => levels,
+        None => return,
+    };
+    let mut declared = HashSet::new();
+    for level in levels {
+        if !declared.insert(level.trim()) {
+            diag.add_error(
+                Some(module),
+                format!("V14: Level {} is declared more than once.", level),
+            );
+        }
+    }
+    for (id, node) in nodes.iter().filter(|(_, n)| n.module == module) {
+        if let Some(level) = &node.level {
+            if !declared.contains(level.trim()) {
+                diag.add_error_at(
+                    Some(module),
+                    Some(id),
+                    node.location.as_ref(),
+                    format!(
+                        "V14: Element {} has undeclared level {}{}.",
+                        get_display_id(id, module),
+                        level,
+                        format_suggestions(&get_suggestions(
+                            level.trim(),
+                            levels.iter().map(|l| l.as_str())
+                        ))
+                    ),
+                );
+            }
+        }
     }
 }
 
@@ -94,36 +553,58 @@ fn validate_references(diag: &mut Diagnostics, module: &str, id: &str, node: &Gs
 ///
 fn validate_reference(
     diag: &mut Diagnostics,
-    module: &str,
     node: &str,
-    refs: &[String],
+    gsn_node: &GsnNode,
+    attribute: &str,
     diag_str: &str,
-    valid_refs: &[&str],
+    valid_refs: &[GsnNodeType],
+    nodes: &MyMap<String, GsnNode>,
 ) {
+    let refs = gsn_node.get_references(attribute);
     // HashSet ok, since order is never important.
     let mut set = HashSet::with_capacity(refs.len());
-    for n in refs {
+    for (index, n) in refs.iter().enumerate() {
+        let location = gsn_node.get_reference_location_at(attribute, index);
         if n == node {
-            diag.add_error(
-                Some(module),
-                format!("V06: Element {} references itself in {}.", node, diag_str),
+            diag.add_error_at(
+                Some(&gsn_node.module),
+                Some(node),
+                location,
+                format!(
+                    "V06: Element {} references itself in {}.",
+                    get_display_id(node, &gsn_node.module),
+                    diag_str
+                ),
             );
         }
         if !set.insert(n) {
-            diag.add_warning(
-                Some(module),
+            diag.add_reference_warning_at(
+                Some(&gsn_node.module),
+                Some(node),
+                n,
+                location,
                 format!(
                     "V05: Element {} has duplicate entry {} in {}.",
-                    node, n, diag_str
+                    get_display_id(node, &gsn_node.module),
+                    get_display_id(n, &gsn_node.module),
+                    diag_str
                 ),
             );
         }
-        if !valid_refs.iter().any(|&r| n.starts_with(r)) {
-            diag.add_error(
-                Some(module),
+        if !get_node_type(nodes, n)
+            .map(|t| valid_refs.contains(&t))
+            .unwrap_or(false)
+        {
+            diag.add_reference_error_at(
+                Some(&gsn_node.module),
+                Some(node),
+                n,
+                location,
                 format!(
                     "V04: Element {} has invalid type of reference {} in {}.",
-                    node, n, diag_str
+                    get_display_id(node, &gsn_node.module),
+                    get_display_id(n, &gsn_node.module),
+                    diag_str
                 ),
             );
         }
@@ -133,10 +614,11 @@ fn validate_reference(
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::gsn::{get_suppressions, Choice, ModuleInformation};
     #[test]
     fn unknown_id() {
         let mut d = Diagnostics::default();
-        validate_id(&mut d, "", "X1");
+        validate_id(&mut d, "", "X1", None);
         assert_eq!(d.messages.len(), 1);
         assert_eq!(d.messages[0].module, Some("".to_owned()));
         assert_eq!(d.messages[0].diag_type, DiagType::Error);
@@ -151,7 +633,7 @@ mod test {
     #[test]
     fn known_id() {
         let mut d = Diagnostics::default();
-        validate_id(&mut d, "", "Sn1");
+        validate_id(&mut d, "", "Sn1", None);
         assert_eq!(d.messages.len(), 0);
         assert_eq!(d.errors, 0);
         assert_eq!(d.warnings, 0);
@@ -186,6 +668,28 @@ mod test {
         assert_eq!(d.warnings, 0);
     }
 
+    #[test]
+    fn suppressed_self_ref() {
+        let mut d = Diagnostics::default();
+        let mut nodes = MyMap::<String, GsnNode>::new();
+        nodes.insert(
+            "C1".to_owned(),
+            GsnNode {
+                in_context_of: Some(vec!["C1".to_owned()]),
+                suppress: Some(vec!["V06".to_owned()]),
+                ..Default::default()
+            },
+        );
+        d.suppressions = get_suppressions(&nodes);
+        validate_module(&mut d, "", &nodes);
+        assert_eq!(d.messages.len(), 1);
+        assert_eq!(
+            d.messages[0].msg,
+            "V04: Element C1 has invalid type of reference C1 in context."
+        );
+        assert_eq!(d.errors, 1);
+    }
+
     #[test]
     fn self_ref_support() {
         let mut d = Diagnostics::default();
@@ -324,6 +828,36 @@ mod test {
         assert_eq!(d.warnings, 1);
     }
 
+    #[test]
+    fn duplicate_ref_location() {
+        let mut d = Diagnostics::default();
+        let mut nodes = MyMap::<String, GsnNode>::new();
+        let mut locations =
+            crate::yaml_fix::get_locations("test.yaml", "G1:\n  supportedBy: [G2, Sn1, G2]\n");
+        nodes.insert(
+            "G1".to_owned(),
+            GsnNode {
+                supported_by: Some(vec!["G2".to_owned(), "Sn1".to_owned(), "G2".to_owned()]),
+                reference_locations: locations.references.remove("G1").unwrap(),
+                ..Default::default()
+            },
+        );
+        nodes.insert(
+            "G2".to_owned(),
+            GsnNode {
+                undeveloped: Some(true),
+                ..Default::default()
+            },
+        );
+        nodes.insert("Sn1".to_owned(), GsnNode::default());
+        validate_module(&mut d, "", &nodes);
+        assert_eq!(d.messages.len(), 1);
+        assert_eq!(
+            d.messages[0].location.as_ref().unwrap().to_string(),
+            "test.yaml:2:26"
+        );
+    }
+
     #[test]
     fn wrong_ref_context() {
         let mut d = Diagnostics::default();
@@ -485,4 +1019,419 @@ mod test {
         assert_eq!(d.errors, 1);
         assert_eq!(d.warnings, 0);
     }
+
+    #[test]
+    fn explicit_type() {
+        let mut d = Diagnostics::default();
+        let mut nodes = MyMap::<String, GsnNode>::new();
+        nodes.insert(
+            "HAZ-G-0042".to_owned(),
+            GsnNode {
+                node_type: Some("goal".to_owned()),
+                supported_by: Some(vec!["HAZ-EV-0001".to_owned()]),
+                in_context_of: Some(vec!["HAZ-CTX-0001".to_owned()]),
+                ..Default::default()
+            },
+        );
+        nodes.insert(
+            "HAZ-EV-0001".to_owned(),
+            GsnNode {
+                node_type: Some("Solution".to_owned()),
+                ..Default::default()
+            },
+        );
+        nodes.insert(
+            "HAZ-CTX-0001".to_owned(),
+            GsnNode {
+                node_type: Some("context".to_owned()),
+                ..Default::default()
+            },
+        );
+        validate_module(&mut d, "", &nodes);
+        assert_eq!(d.messages.len(), 0);
+        assert_eq!(d.errors, 0);
+        assert_eq!(d.warnings, 0);
+    }
+
+    #[test]
+    fn explicit_type_overrides_prefix() {
+        let mut d = Diagnostics::default();
+        let mut nodes = MyMap::<String, GsnNode>::new();
+        nodes.insert(
+            "G1".to_owned(),
+            GsnNode {
+                supported_by: Some(vec!["C1".to_owned()]),
+                ..Default::default()
+            },
+        );
+        nodes.insert(
+            "C1".to_owned(),
+            GsnNode {
+                node_type: Some("goal".to_owned()),
+                undeveloped: Some(true),
+                ..Default::default()
+            },
+        );
+        validate_module(&mut d, "", &nodes);
+        assert_eq!(d.messages.len(), 0);
+        assert_eq!(d.errors, 0);
+        assert_eq!(d.warnings, 0);
+    }
+
+    #[test]
+    fn unknown_type() {
+        let mut d = Diagnostics::default();
+        let mut nodes = MyMap::<String, GsnNode>::new();
+        nodes.insert(
+            "G1".to_owned(),
+            GsnNode {
+                node_type: Some("gaol".to_owned()),
+                ..Default::default()
+            },
+        );
+        validate_module(&mut d, "", &nodes);
+        assert_eq!(d.messages.len(), 1);
+        assert_eq!(d.messages[0].module, Some("".to_owned()));
+        assert_eq!(d.messages[0].diag_type, DiagType::Error);
+        assert_eq!(
+            d.messages[0].msg,
+            "V01: Element G1 has unknown type gaol (did you mean goal?). Please see README for supported types"
+        );
+        assert_eq!(d.errors, 1);
+        assert_eq!(d.warnings, 0);
+    }
+
+    #[test]
+    fn module_reference() {
+        let mut d = Diagnostics::default();
+        let mut nodes = MyMap::<String, GsnNode>::new();
+        let mut modules = HashMap::<String, Module>::new();
+        modules.insert(
+            "Sub1".to_owned(),
+            Module {
+                filename: "sub1.gsn.yaml".to_owned(),
+                meta: None,
+            },
+        );
+        nodes.insert(
+            "G1".to_owned(),
+            GsnNode {
+                supported_by: Some(vec!["M1".to_owned(), "M2".to_owned(), "M3".to_owned()]),
+                ..Default::default()
+            },
+        );
+        nodes.insert(
+            "M1".to_owned(),
+            GsnNode {
+                node_type: Some("module".to_owned()),
+                module_ref: Some("Sub1".to_owned()),
+                ..Default::default()
+            },
+        );
+        nodes.insert(
+            "M2".to_owned(),
+            GsnNode {
+                node_type: Some("module".to_owned()),
+                module_ref: Some("Sub2".to_owned()),
+                ..Default::default()
+            },
+        );
+        nodes.insert(
+            "M3".to_owned(),
+            GsnNode {
+                node_type: Some("module".to_owned()),
+                ..Default::default()
+            },
+        );
+        validate_module(&mut d, "", &nodes);
+        validate_module_references(&mut d, "", &nodes, &modules);
+        assert_eq!(d.messages.len(), 2);
+        assert_eq!(d.messages[0].module, Some("".to_owned()));
+        assert_eq!(d.messages[0].diag_type, DiagType::Error);
+        assert_eq!(
+            d.messages[0].msg,
+            "V07: Module reference M2 refers to unknown module Sub2."
+        );
+        assert_eq!(d.messages[1].diag_type, DiagType::Error);
+        assert_eq!(
+            d.messages[1].msg,
+            "V07: Module reference M3 does not refer to a module."
+        );
+        assert_eq!(d.errors, 2);
+        assert_eq!(d.warnings, 0);
+    }
+
+    #[test]
+    fn pattern_annotations() {
+        let mut d = Diagnostics::default();
+        let mut nodes = MyMap::<String, GsnNode>::new();
+        let mut multiplicity = MyMap::<String, String>::new();
+        multiplicity.insert("G2".to_owned(), "at least 2".to_owned());
+        multiplicity.insert("G3".to_owned(), "some".to_owned());
+        multiplicity.insert("G4".to_owned(), "n".to_owned());
+        nodes.insert(
+            "G1".to_owned(),
+            GsnNode {
+                supported_by: Some(vec!["G2".to_owned(), "G3".to_owned()]),
+                multiplicity: Some(multiplicity),
+                choice: Some(Choice {
+                    elements: vec!["G2".to_owned()],
+                    label: None,
+                }),
+                ..Default::default()
+            },
+        );
+        validate_pattern(&mut d, "", &nodes, &HashMap::new());
+        assert_eq!(d.messages.len(), 4);
+        assert_eq!(
+            d.messages[0].msg,
+            "V08: Element G1 has invalid multiplicity some for G3."
+        );
+        assert_eq!(
+            d.messages[1].msg,
+            "V08: Multiplicity of element G1 refers to G4 which is neither in supportedBy nor inContextOf."
+        );
+        assert_eq!(
+            d.messages[2].msg,
+            "V08: Choice of element G1 must have at least two elements."
+        );
+        assert_eq!(d.messages[3].diag_type, DiagType::Warning);
+        assert_eq!(
+            d.messages[3].msg,
+            "V09: Element G1 uses the pattern extension, but module  is not a pattern."
+        );
+        assert_eq!(d.errors, 3);
+        assert_eq!(d.warnings, 1);
+    }
+
+    #[test]
+    fn pattern_module() {
+        let mut d = Diagnostics::default();
+        let mut nodes = MyMap::<String, GsnNode>::new();
+        let mut modules = HashMap::<String, Module>::new();
+        modules.insert(
+            "Pattern".to_owned(),
+            Module {
+                filename: "pattern.gsn.yaml".to_owned(),
+                meta: Some(ModuleInformation {
+                    name: "Pattern".to_owned(),
+                    brief: None,
+                    pattern: Some(true),
+                    confidence_for: None,
+                    levels: None,
+                    additional: MyMap::new(),
+                }),
+            },
+        );
+        nodes.insert(
+            "G1".to_owned(),
+            GsnNode {
+                supported_by: Some(vec!["G2".to_owned(), "G3".to_owned()]),
+                choice: Some(Choice {
+                    elements: vec!["G2".to_owned(), "G3".to_owned()],
+                    label: Some("1 of 2".to_owned()),
+                }),
+                module: "Pattern".to_owned(),
+                ..Default::default()
+            },
+        );
+        nodes.insert(
+            "G2".to_owned(),
+            GsnNode {
+                undeveloped: Some(true),
+                uninstantiated: Some(true),
+                module: "Pattern".to_owned(),
+                ..Default::default()
+            },
+        );
+        validate_pattern(&mut d, "Pattern", &nodes, &modules);
+        assert_eq!(d.messages.len(), 0);
+        assert_eq!(d.errors, 0);
+        assert_eq!(d.warnings, 0);
+    }
+
+    #[test]
+    fn acps() {
+        let mut d = Diagnostics::default();
+        let mut nodes = MyMap::<String, GsnNode>::new();
+        let mut modules = HashMap::<String, Module>::new();
+        modules.insert(
+            "Confidence".to_owned(),
+            Module {
+                filename: "confidence.gsn.yaml".to_owned(),
+                meta: Some(ModuleInformation {
+                    name: "Confidence".to_owned(),
+                    brief: None,
+                    pattern: None,
+                    confidence_for: Some("ACP3".to_owned()),
+                    levels: None,
+                    additional: MyMap::new(),
+                }),
+            },
+        );
+        let mut acp1 = MyMap::new();
+        acp1.insert("ACP1".to_owned(), None);
+        acp1.insert("ACP2".to_owned(), Some("Sn2".to_owned()));
+        nodes.insert(
+            "G1".to_owned(),
+            GsnNode {
+                supported_by: Some(vec!["Sn1".to_owned()]),
+                acp: Some(acp1),
+                ..Default::default()
+            },
+        );
+        let mut acp2 = MyMap::new();
+        acp2.insert("ACP1".to_owned(), None);
+        nodes.insert(
+            "G2".to_owned(),
+            GsnNode {
+                supported_by: Some(vec!["Sn1".to_owned()]),
+                acp: Some(acp2),
+                ..Default::default()
+            },
+        );
+        validate_acps(&mut d, "", &nodes, &modules);
+        validate_acps(&mut d, "Confidence", &nodes, &modules);
+        assert_eq!(d.messages.len(), 3);
+        assert_eq!(
+            d.messages[0].msg,
+            "V10: ACP ACP2 of element G1 refers to Sn2 which is neither in supportedBy nor inContextOf."
+        );
+        assert_eq!(
+            d.messages[1].msg,
+            "V10: ACP ACP1 of element G2 is already defined for element G1."
+        );
+        assert_eq!(
+            d.messages[2].msg,
+            "V11: Module Confidence is a confidence argument for unknown ACP ACP3."
+        );
+        assert_eq!(d.errors, 3);
+    }
+
+    #[test]
+    fn challenges() {
+        let mut d = Diagnostics::default();
+        let mut nodes = MyMap::<String, GsnNode>::new();
+        nodes.insert(
+            "G1".to_owned(),
+            GsnNode {
+                supported_by: Some(vec!["Sn1".to_owned()]),
+                ..Default::default()
+            },
+        );
+        nodes.insert("Sn1".to_owned(), GsnNode::default());
+        nodes.insert(
+            "CG1".to_owned(),
+            GsnNode {
+                node_type: Some("counterGoal".to_owned()),
+                challenges: Some("G1 -> Sn1".to_owned()),
+                supported_by: Some(vec!["CS1".to_owned()]),
+                ..Default::default()
+            },
+        );
+        nodes.insert(
+            "CS1".to_owned(),
+            GsnNode {
+                node_type: Some("counterSolution".to_owned()),
+                ..Default::default()
+            },
+        );
+        nodes.insert(
+            "CG2".to_owned(),
+            GsnNode {
+                node_type: Some("defeater".to_owned()),
+                challenges: Some("G1 -> Sn2".to_owned()),
+                undeveloped: Some(true),
+                ..Default::default()
+            },
+        );
+        nodes.insert(
+            "G2".to_owned(),
+            GsnNode {
+                challenges: Some("G1".to_owned()),
+                undeveloped: Some(true),
+                ..Default::default()
+            },
+        );
+        validate_module(&mut d, "", &nodes);
+        assert_eq!(d.messages.len(), 2);
+        assert_eq!(
+            d.messages[0].msg,
+            "V12: Element CG2 challenges the link from G1 to Sn2, which does not exist."
+        );
+        assert_eq!(
+            d.messages[1].msg,
+            "V12: Element G2 challenges G1, but only counter goals and counter solutions can challenge."
+        );
+        assert_eq!(d.errors, 2);
+        assert_eq!(d.warnings, 0);
+    }
+
+    #[test]
+    fn confidences() {
+        use crate::gsn::confidence::Confidence;
+        let mut d = Diagnostics::default();
+        let mut nodes = MyMap::<String, GsnNode>::new();
+        let mut weights = MyMap::new();
+        weights.insert("Sn1".to_owned(), 2.0);
+        weights.insert("Sn3".to_owned(), -1.0);
+        nodes.insert(
+            "S1".to_owned(),
+            GsnNode {
+                supported_by: Some(vec!["Sn1".to_owned(), "Sn2".to_owned()]),
+                combination: Some(Combination::Weighted(weights)),
+                ..Default::default()
+            },
+        );
+        nodes.insert(
+            "Sn1".to_owned(),
+            GsnNode {
+                confidence: Some(Confidence::Value(0.8)),
+                ..Default::default()
+            },
+        );
+        nodes.insert(
+            "Sn2".to_owned(),
+            GsnNode {
+                confidence: Some(Confidence::Opinion {
+                    belief: 0.8,
+                    disbelief: 0.8,
+                    uncertainty: 0.0,
+                }),
+                ..Default::default()
+            },
+        );
+        nodes.insert(
+            "G1".to_owned(),
+            GsnNode {
+                supported_by: Some(vec!["S1".to_owned()]),
+                confidence: Some(Confidence::Value(0.5)),
+                combination: Some(Combination::Conjunctive),
+                ..Default::default()
+            },
+        );
+        validate_module(&mut d, "", &nodes);
+        assert_eq!(d.messages.len(), 5);
+        assert_eq!(
+            d.messages[0].msg,
+            "V13: Element G1 has a confidence, but is not a solution."
+        );
+        assert_eq!(
+            d.messages[1].msg,
+            "V13: Element G1 has a combination, but is not a strategy."
+        );
+        assert_eq!(
+            d.messages[2].msg,
+            "V13: Weight of element S1 refers to Sn3 which is not in supportedBy."
+        );
+        assert_eq!(
+            d.messages[3].msg,
+            "V13: Weight of element S1 for Sn3 is negative."
+        );
+        assert_eq!(
+            d.messages[4].msg,
+            "V13: Confidence of element Sn2 must be between 0 and 1 and an opinion must sum up to 1."
+        );
+        assert_eq!(d.errors, 5);
+    }
 }
@@ -2,20 +2,25 @@ use anyhow::{anyhow, Context, Result};
 use clap::Arg;
 use std::collections::HashMap;
 use std::fs::File;
-use std::io::BufReader;
+use std::io::IsTerminal;
 
+mod config;
 mod diagnostics;
 mod dirgraphsvg;
+mod fix;
 mod gsn;
 mod render;
 mod yaml_fix;
 
-use diagnostics::Diagnostics;
+use diagnostics::baseline::{read_baseline, write_baseline};
+use diagnostics::render::{render_messages, MessageFormat};
+use diagnostics::{DiagType, Diagnostics, Severity};
 use dirgraphsvg::escape_text;
 use gsn::{GsnDocumentNode, GsnNode, Module, ModuleInformation};
 use yaml_fix::MyMap;
 
 const MODULE_INFOMRATION_NODE: &str = "module";
+const MAX_FIX_PASSES: usize = 3;
 
 ///
 /// Main entry point.
@@ -45,6 +50,112 @@ fn main() -> Result<()> {
                 .takes_value(true)
                 .help_heading("CHECKS"),
         )
+        .arg(
+            Arg::new("CONFIDENCE_THRESHOLD")
+                .help("Warn if the confidence of a goal is below <CONFIDENCE_THRESHOLD>.")
+                .short('t')
+                .long("confidence-threshold")
+                .takes_value(true)
+                .multiple_occurrences(false)
+                .help_heading("CHECKS"),
+        )
+        .arg(
+            Arg::new("LINT")
+                .help("Enable the structural lints, either all or only the given rules e.g., --lint=L01,unexplained-strategy.")
+                .long("lint")
+                .takes_value(true)
+                .min_values(0)
+                .require_equals(true)
+                .use_value_delimiter(true)
+                .value_name("RULES")
+                .help_heading("CHECKS"),
+        )
+        .arg(
+            Arg::new("MAX_SOLUTIONS")
+                .help("Maximum number of solutions directly supporting a goal (lint L02).")
+                .long("lint-max-solutions")
+                .takes_value(true)
+                .value_name("N")
+                .help_heading("CHECKS"),
+        )
+        .arg(
+            Arg::new("CHECK_EVIDENCE")
+                .help("Check that local evidence files given by url of solutions exist and match their sha256 attribute.")
+                .long("check-evidence")
+                .help_heading("CHECKS"),
+        )
+        .arg(
+            Arg::new("CONFIG")
+                .help("Read the severities of diagnostic codes, the options of lints and thresholds of metrics from the configuration file <CONFIG>.")
+                .long("config")
+                .takes_value(true)
+                .multiple_occurrences(false)
+                .help_heading("CHECKS"),
+        )
+        .arg(
+            Arg::new("ALLOW")
+                .help("Do not report warnings with diagnostic code <ALLOW>.")
+                .long("allow")
+                .takes_value(true)
+                .multiple_occurrences(true)
+                .use_value_delimiter(true)
+                .help_heading("CHECKS"),
+        )
+        .arg(
+            Arg::new("WARN")
+                .help("Report warnings with diagnostic code <WARN> as warnings.")
+                .long("warn")
+                .takes_value(true)
+                .multiple_occurrences(true)
+                .use_value_delimiter(true)
+                .help_heading("CHECKS"),
+        )
+        .arg(
+            Arg::new("DENY")
+                .help("Report warnings with diagnostic code <DENY> as errors.")
+                .long("deny")
+                .takes_value(true)
+                .multiple_occurrences(true)
+                .use_value_delimiter(true)
+                .help_heading("CHECKS"),
+        )
+        .arg(
+            Arg::new("FIX")
+                .help("Fix duplicate entries (V05), undeveloped elements (V02) and context in supporting elements (V04) in the input file(s).")
+                .long("fix")
+                .help_heading("CHECKS"),
+        )
+        .arg(
+            Arg::new("BASELINE")
+                .help("Do not report warnings that are already known in the baseline file <BASELINE>.")
+                .long("baseline")
+                .takes_value(true)
+                .multiple_occurrences(false)
+                .help_heading("CHECKS"),
+        )
+        .arg(
+            Arg::new("WRITE_BASELINE")
+                .help("Write all current warnings to the baseline file <WRITE_BASELINE>.")
+                .long("write-baseline")
+                .takes_value(true)
+                .multiple_occurrences(false)
+                .help_heading("CHECKS"),
+        )
+        .arg(
+            Arg::new("DENY_WARNINGS")
+                .help("Fail if any warnings are reported.")
+                .long("deny-warnings")
+                .help_heading("CHECKS"),
+        )
+        .arg(
+            Arg::new("MESSAGE_FORMAT")
+                .help("Format of error messages and warnings (default: rich on a terminal, plain otherwise). Machine-readable formats are written to stdout.")
+                .long("message-format")
+                .takes_value(true)
+                .possible_values(["plain", "rich", "json", "sarif", "junit", "github"])
+                .multiple_occurrences(false)
+                .help_heading("CHECKS"),
+        )
         .arg(
             Arg::new("NO_ARGUMENT_VIEW")
                 .help("Do not output of argument view for provided input files.")
@@ -108,6 +219,24 @@ fn main() -> Result<()> {
                 .conflicts_with("EVIDENCES")
                 .help_heading("OUTPUT"),
         )
+        .arg(
+            Arg::new("CONFIDENCE_REPORT")
+                .help("Output the confidence scores of all goals to <CONFIDENCE_REPORT>.")
+                .short('b')
+                .long("confidence-report")
+                .takes_value(true)
+                .multiple_occurrences(false)
+                .conflicts_with("CHECKONLY")
+                .help_heading("OUTPUT"),
+        )
+        .arg(
+            Arg::new("METRICS")
+                .help("Output metrics of the argument and of each module to <METRICS>.")
+                .long("metrics")
+                .takes_value(true)
+                .multiple_occurrences(false)
+                .help_heading("OUTPUT"),
+        )
         .arg(
             Arg::new("LAYERS")
                 .help("Output additional layer.")
@@ -154,9 +283,43 @@ fn main() -> Result<()> {
                 .long("full-legend")
                 .conflicts_with("CHECKONLY")
                 .help_heading("OUTPUT MODIFICATION"),
+        )
+        .arg(
+            Arg::new("INSTANTIATE")
+                .help("Instantiate the pattern with the data (YAML or CSV) in <INSTANTIATE>.")
+                .short('i')
+                .long("instantiate")
+                .takes_value(true)
+                .conflicts_with("CHECKONLY")
+                .help_heading("PATTERNS"),
+        )
+        .arg(
+            Arg::new("INSTANCE")
+                .help("Output the instantiated module to <INSTANCE>.")
+                .short('o')
+                .long("instance")
+                .takes_value(true)
+                .requires("INSTANTIATE")
+                .help_heading("PATTERNS"),
         );
     let matches = app.get_matches();
     let mut diags = Diagnostics::default();
+    let config = matches
+        .value_of("CONFIG")
+        .map(config::read_config)
+        .transpose()?
+        .unwrap_or_default();
+    diags.severities = config.severity;
+    // Command line takes precedence over the configuration file
+    for (arg, severity) in [
+        ("ALLOW", Severity::Allow),
+        ("WARN", Severity::Warn),
+        ("DENY", Severity::Deny),
+    ] {
+        for code in matches.values_of(arg).into_iter().flatten() {
+            diags.severities.insert(code.to_owned(), severity);
+        }
+    }
     let inputs: Vec<&str> = matches.values_of("INPUT").unwrap().collect();
     let mut nodes = MyMap::<String, GsnNode>::new();
     let layers = matches
@@ -174,15 +337,120 @@ fn main() -> Result<()> {
     // Read input
     read_inputs(&inputs, &mut nodes, &mut modules, &mut diags)?;
 
+    let confidence_threshold = matches
+        .value_of("CONFIDENCE_THRESHOLD")
+        .map(|t| t.parse::<f64>())
+        .transpose()
+        .context("Confidence threshold must be a number")?;
+
+    let lints = matches
+        .is_present("LINT")
+        .then(|| {
+            let mut options = config.lint;
+            if let Some(max_solutions) = matches.value_of("MAX_SOLUTIONS") {
+                options.max_solutions = max_solutions
+                    .parse::<usize>()
+                    .context("Maximum number of solutions must be a number")?;
+            }
+            let rules: Vec<&str> = matches.values_of("LINT").into_iter().flatten().collect();
+            gsn::lint::Lints::new(&rules, options)
+        })
+        .transpose()?;
+
     // Validate
-    validate_and_check(&nodes, &modules, &mut diags, excluded_modules, &layers);
+    validate_and_check(
+        &nodes,
+        &modules,
+        &mut diags,
+        excluded_modules.to_owned(),
+        &layers,
+        confidence_threshold,
+        lints.as_ref(),
+    );
 
-    if diags.errors == 0 && !matches.is_present("CHECKONLY") {
-        // Output argument view
-        print_outputs(&matches, nodes, &modules, &layers, stylesheets)?;
+    if matches.is_present("FIX") {
+        // Fixes can reveal new problems e.g., V02 after all supporting elements are moved to the context.
+        for _ in 0..MAX_FIX_PASSES {
+            let fixes = fix::get_fixes(&nodes, &diags);
+            let applied = fix::apply_fixes(&fixes)?;
+            if applied.is_empty() {
+                break;
+            }
+            for fix in applied {
+                eprintln!("Fixed: {}: {}", fix.file, fix);
+            }
+            // Check the fixed input files again
+            nodes = MyMap::new();
+            modules = HashMap::new();
+            diags = Diagnostics {
+                severities: std::mem::take(&mut diags.severities),
+                ..Default::default()
+            };
+            read_inputs(&inputs, &mut nodes, &mut modules, &mut diags)?;
+            validate_and_check(
+                &nodes,
+                &modules,
+                &mut diags,
+                excluded_modules.to_owned(),
+                &layers,
+                confidence_threshold,
+                lints.as_ref(),
+            );
+        }
+    }
+
+    if matches.is_present("CHECK_EVIDENCE") {
+        gsn::check::check_evidence_files(
+            &mut diags,
+            &nodes,
+            &modules,
+            excluded_modules.as_deref().unwrap_or_default(),
+        );
+    }
+
+    // Metrics require an argument without errors e.g., cycles.
+    if diags.errors == 0 {
+        if let Some(output_filename) = matches.value_of("METRICS") {
+            let mut output_file = File::create(output_filename)
+                .context(format!("Failed to open output file {}", output_filename))?;
+            render::render_metrics(&mut output_file, &nodes, &modules)?;
+        }
+        if !config.thresholds.is_empty() {
+            let metrics = gsn::metrics::get_metrics(&nodes, None);
+            gsn::metrics::check_thresholds(&mut diags, &metrics, &config.thresholds)?;
+        }
+    }
+
+    if diags.errors == 0 {
+        if let Some(data) = matches.value_of("INSTANTIATE") {
+            // Instantiate pattern instead of outputting views
+            instantiate_pattern(&matches, data, &inputs, &nodes, &modules, &mut diags)?;
+        } else if !matches.is_present("CHECKONLY") {
+            // Output argument view
+            print_outputs(&matches, nodes, &modules, &layers, stylesheets)?;
+        }
+    }
+    if let Some(baseline) = matches.value_of("WRITE_BASELINE") {
+        write_baseline(baseline, &diags)?;
+    }
+    if let Some(baseline) = matches.value_of("BASELINE") {
+        diags.apply_baseline(&read_baseline(baseline)?);
     }
     // Output diagnostic messages
-    output_messages(&diags)
+    // Messages for humans are written to stderr, thus, its terminal decides about the default format.
+    let is_terminal = std::io::stderr().is_terminal();
+    let format = match matches.value_of("MESSAGE_FORMAT") {
+        Some("plain") => MessageFormat::Plain,
+        Some("json") => MessageFormat::Json,
+        Some("sarif") => MessageFormat::Sarif,
+        Some("junit") => MessageFormat::Junit,
+        Some("github") => MessageFormat::Github,
+        Some(_) => MessageFormat::Rich,
+        None if is_terminal => MessageFormat::Rich,
+        None => MessageFormat::Plain,
+    };
+    let color = std::env::var_os("NO_COLOR").is_none();
+    output_messages(&diags, format, color, matches.is_present("DENY_WARNINGS"))
 }
 
 ///
@@ -195,11 +463,12 @@ fn read_inputs(
     modules: &mut HashMap<String, Module>,
     diags: &mut Diagnostics,
 ) -> Result<(), anyhow::Error> {
+    let mut module_nodes: Vec<(String, GsnNode)> = Vec::new();
     for input in inputs {
-        let reader =
-            BufReader::new(File::open(&input).context(format!("Failed to open file {}", input))?);
+        let contents =
+            std::fs::read_to_string(input).context(format!("Failed to open file {}", input))?;
 
-        let mut n: MyMap<String, GsnDocumentNode> = serde_yaml::from_reader(reader)
+        let mut n: MyMap<String, GsnDocumentNode> = serde_yaml::from_str(&contents)
             .map_err(|e| {
                 anyhow!(format!(
                     "No valid GSN element can be found starting from line {}",
@@ -207,6 +476,7 @@ fn read_inputs(
                 ))
             })
             .context(format!("Failed to parse YAML from file {}", input))?;
+        let mut locations = yaml_fix::get_locations(input, &contents);
         let meta: Option<ModuleInformation> = match n.remove_entry(MODULE_INFOMRATION_NODE) {
             Some((_, GsnDocumentNode::ModuleInformation(x))) => Some(x),
             _ => None,
@@ -224,8 +494,10 @@ fn read_inputs(
                 meta,
             });
         } else {
-            diags.add_error(
+            diags.add_error_at(
                 Some(&module),
+                Some(MODULE_INFOMRATION_NODE),
+                locations.nodes.get(MODULE_INFOMRATION_NODE),
                 format!(
                     "C06: Module name {} in {} was already present in {}.",
                     module,
@@ -235,34 +507,53 @@ fn read_inputs(
             );
         }
 
-        // Check for duplicates, since they might be in separate files.
+        // Check for duplicates, since modules with the same name might be in separate files.
         let node_names: Vec<String> = n.keys().cloned().collect();
         for node_name in node_names {
             if let Some((k, v)) = n.remove_entry(&node_name) {
-                if let std::collections::btree_map::Entry::Vacant(e) = nodes.entry(k.to_owned()) {
+                if let Some((_, other)) = module_nodes
+                    .iter()
+                    .find(|(id, other)| id == &k && other.module == module)
+                {
+                    // The first definition might be in another file with the same module name (C06).
+                    let first_file = other
+                        .location
+                        .as_ref()
+                        .map(|l| l.file.as_str())
+                        .unwrap_or(input);
+                    diags.add_located_msg(
+                        DiagType::Error,
+                        Some(&other.module),
+                        Some(&gsn::get_qualified_id(&module, &k)),
+                        locations.nodes.get(&k),
+                        other
+                            .location
+                            .iter()
+                            .map(|l| (l.to_owned(), "first defined here".to_owned()))
+                            .collect(),
+                        format!(
+                            "C07: Element {} in {} was already present in {} (module {}).",
+                            k, input, first_file, other.module
+                        ),
+                    );
+                } else {
                     match v {
                         GsnDocumentNode::GsnNode(mut x) => {
-                            // Remember module for node
+                            // Remember module and locations for node
                             x.module = module.to_owned();
-                            e.insert(x);
+                            x.location = locations.nodes.remove(&k);
+                            x.reference_locations =
+                                locations.references.remove(&k).unwrap_or_default();
+                            module_nodes.push((k, *x));
                         }
                         _ => unreachable!(), // There can be only one MetaNode
                     }
-                } else {
-                    diags.add_error(
-                        Some(&module),
-                        format!(
-                            "C07: Element {} in {} was already present in {}.",
-                            k,
-                            input,
-                            nodes.get(&k).unwrap().module
-                        ),
-                    );
-                    break;
                 }
             }
         }
     }
+    // Identifiers only need to be unique per module.
+    nodes.append(&mut gsn::merge_modules(module_nodes));
     Ok(())
 }
 
@@ -278,20 +569,91 @@ fn validate_and_check(
     diags: &mut Diagnostics,
     excluded_modules: Option<Vec<&str>>,
     layers: &Option<Vec<&str>>,
+    confidence_threshold: Option<f64>,
+    lints: Option<&gsn::lint::Lints>,
 ) {
+    diags.suppressions = gsn::get_suppressions(nodes);
     for module in modules.keys() {
         // Validation for wellformedness is done unconditionally.
         gsn::validation::validate_module(diags, module, nodes);
-        if diags.errors > 0 {
-            break;
-        }
+        gsn::validation::validate_module_references(diags, module, nodes, modules);
+        gsn::validation::validate_pattern(diags, module, nodes, modules);
+        gsn::validation::validate_acps(diags, module, nodes, modules);
+        gsn::validation::validate_levels(diags, module, nodes, modules);
+    }
+    let ex_mods = excluded_modules.clone().unwrap_or_default();
+    let acyclic = gsn::check::check_nodes(diags, nodes, excluded_modules);
+    gsn::check::check_level_order(diags, nodes, modules);
+    if let Some(lays) = &layers {
+        gsn::check::check_layers(diags, nodes, lays);
+    }
+    if let Some(lints) = lints {
+        gsn::lint::lint_nodes(diags, nodes, lints, &ex_mods);
     }
+    // Propagation of confidences requires an acyclic graph.
+    if let (true, Some(threshold)) = (acyclic, confidence_threshold) {
+        gsn::check::check_confidence(diags, nodes, threshold);
+    }
+}
+
+///
+/// Instantiate a pattern
+///
+/// The instance is written to the file given with `-o` or
+/// to the data file with the extension replaced by `.gsn.yaml`.
+/// The name of the instantiated module is derived from the file name.
+///
+fn instantiate_pattern(
+    matches: &clap::ArgMatches,
+    data: &str,
+    inputs: &[&str],
+    nodes: &MyMap<String, GsnNode>,
+    modules: &HashMap<String, Module>,
+    diags: &mut Diagnostics,
+) -> Result<(), anyhow::Error> {
+    if inputs.len() != 1 {
+        return Err(anyhow!(
+            "Exactly one pattern must be provided for instantiation."
+        ));
+    }
+    let (pattern, pattern_module) = modules
+        .iter()
+        .next()
+        .ok_or_else(|| anyhow!("No pattern found."))?;
+    let output_filename = match matches.value_of("INSTANCE") {
+        Some(f) => std::path::PathBuf::from(f),
+        None => std::path::PathBuf::from(data).with_extension("gsn.yaml"),
+    };
+    let module = output_filename
+        .file_name()
+        .and_then(|f| f.to_str())
+        .and_then(|f| f.split('.').next())
+        .unwrap_or(data)
+        .to_owned();
+    let data = gsn::instantiation::PatternData::from_file(data)?;
+    let instance = gsn::instantiation::instantiate(diags, pattern, &module, nodes, &data);
     if diags.errors == 0 {
-        gsn::check::check_nodes(diags, nodes, excluded_modules);
-        if let Some(lays) = &layers {
-            gsn::check::check_layers(diags, nodes, lays);
-        }
+        let meta = ModuleInformation {
+            name: module,
+            brief: pattern_module
+                .meta
+                .as_ref()
+                .and_then(|m| m.brief.to_owned()),
+            pattern: None,
+            confidence_for: None,
+            levels: pattern_module
+                .meta
+                .as_ref()
+                .and_then(|m| m.levels.to_owned()),
+            additional: MyMap::new(),
+        };
+        let mut output_file = File::create(&output_filename).context(format!(
+            "Failed to open output file {}",
+            output_filename.display()
+        ))?;
+        gsn::instantiation::write_instance(&mut output_file, &meta, &instance)?;
     }
+    Ok(())
 }
 
 ///
@@ -361,7 +723,7 @@ fn print_outputs(
                 .unwrap();
             let mut output_file = File::create(output_filename)
                 .context(format!("Failed to open output file {}", output_filename))?;
-            render::render_complete(&mut output_file, matches, &nodes, stylesheets)?;
+            render::render_complete(&mut output_file, matches, modules, &nodes, stylesheets)?;
         }
     }
     if !matches.is_present("NO_EVIDENCES") {
@@ -375,18 +737,33 @@ fn print_outputs(
             .context(format!("Failed to open output file {}", output_filename))?;
         render::render_evidences(&mut output_file, &nodes, layers)?;
     }
+    if let Some(output_filename) = matches.value_of("CONFIDENCE_REPORT") {
+        let mut output_file = File::create(output_filename)
+            .context(format!("Failed to open output file {}", output_filename))?;
+        render::render_confidence_report(&mut output_file, &nodes)?;
+    }
     Ok(())
 }
 
 ///
 /// Render to dot-file if not only validation is active.
 /// Output summary of warnings and errors.
+/// Colors are used if `color` is set and the messages are written to a terminal.
 ///
-fn output_messages(diags: &Diagnostics) -> Result<()> {
-    for msg in &diags.messages {
-        eprintln!("{}", msg);
+fn output_messages(
+    diags: &Diagnostics,
+    format: MessageFormat,
+    color: bool,
+    deny_warnings: bool,
+) -> Result<()> {
+    if format.is_machine_readable() {
+        render_messages(&mut std::io::stdout(), diags, format, false)?;
+    } else {
+        // Colors are only used if the messages are written to a terminal.
+        let color = color && std::io::stderr().is_terminal();
+        render_messages(&mut std::io::stderr(), diags, format, color)?;
     }
-    if diags.errors == 0 {
+    if diags.errors == 0 && !(deny_warnings && diags.warnings > 0) {
         if diags.warnings > 0 {
             eprintln!("Warning: {} warnings detected.", diags.warnings);
         }
@@ -402,6 +779,7 @@ fn output_messages(diags: &Diagnostics) -> Result<()> {
 
 #[cfg(test)]
 mod test {
+    use crate::diagnostics::render::MessageFormat;
     use crate::diagnostics::Diagnostics;
 
     #[test]
@@ -411,7 +789,7 @@ mod test {
             errors: 3,
             ..Default::default()
         };
-        let res = crate::output_messages(&d);
+        let res = crate::output_messages(&d, MessageFormat::Plain, false, false);
         assert!(res.is_err());
         assert_eq!(
             format!("{:?}", res),
@@ -426,11 +804,25 @@ mod test {
             errors: 0,
             ..Default::default()
         };
-        let res = crate::output_messages(&d);
+        let res = crate::output_messages(&d, MessageFormat::Plain, false, false);
         assert!(res.is_ok());
         assert_eq!(format!("{:?}", res), "Ok(())");
     }
 
+    #[test]
+    fn check_output_messages_deny_warnings() {
+        let d = Diagnostics {
+            warnings: 5,
+            errors: 0,
+            ..Default::default()
+        };
+        let res = crate::output_messages(&d, MessageFormat::Plain, false, true);
+        assert_eq!(
+            format!("{:?}", res),
+            "Err(0 errors and 5 warnings detected.)"
+        );
+    }
+
     #[test]
     fn check_output_messages_no() {
         let d = Diagnostics {
@@ -438,7 +830,7 @@ mod test {
             errors: 0,
             ..Default::default()
         };
-        let res = crate::output_messages(&d);
+        let res = crate::output_messages(&d, MessageFormat::Plain, false, false);
         assert!(res.is_ok());
         assert_eq!(format!("{:?}", res), "Ok(())");
     }
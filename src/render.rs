@@ -1,13 +1,20 @@
-use crate::dirgraphsvg::edges::EdgeType;
+use crate::dirgraphsvg::edges::{EdgeDecorator, EdgeType, SingleEdge};
 use crate::dirgraphsvg::{escape_node_id, escape_text, nodes::*};
-use crate::gsn::{get_levels, GsnNode, Module};
+use crate::gsn::baconian::{get_baconian_scores, has_defeaters};
+use crate::gsn::confidence::{get_confidences, has_confidences};
+use crate::gsn::metrics::{get_metrics, Metrics, METRIC_NAMES};
+use crate::gsn::{
+    get_display_id, get_levels, get_local_id, get_single_module, get_unresolved_defeaters, GsnNode,
+    GsnNodeType, Module, Multiplicity, CHALLENGED_LINK_SEPARATOR,
+};
 use crate::yaml_fix::MyMap;
 use chrono::Utc;
 use std::cell::RefCell;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::io::Write;
 use std::path::{Component, PathBuf};
 use std::rc::Rc;
+use std::str::FromStr;
 
 ///
 ///
@@ -17,16 +24,9 @@ use std::rc::Rc;
 pub fn svg_from_gsn_node(
     id: &str,
     gsn_node: &GsnNode,
+    modules: &HashMap<String, Module>,
 ) -> Rc<RefCell<dyn crate::dirgraphsvg::nodes::Node>> {
-    let layer_classes: Option<Vec<String>> = gsn_node
-        .additional
-        .keys()
-        .map(|k| {
-            let mut t = escape_text(&k.to_ascii_lowercase());
-            t.insert_str(0, "gsn_");
-            Some(t.to_owned())
-        })
-        .collect();
+    let layer_classes = get_layer_classes(gsn_node);
     let mut mod_class = gsn_node.module.to_owned();
     mod_class.insert_str(0, "gsn_module_");
     let classes = gsn_node
@@ -37,37 +37,85 @@ pub fn svg_from_gsn_node(
         .map(|x| Some(x.to_owned()))
         .chain(vec![mod_class].into_iter().map(Some))
         .collect();
-    match id {
-        id if id.starts_with('G') => new_goal(
+    match gsn_node.get_type(id) {
+        Some(GsnNodeType::Goal) => new_goal(
             id,
             &gsn_node.text,
             gsn_node.undeveloped.unwrap_or(false),
             gsn_node.url.to_owned(),
             classes,
         ),
-        id if id.starts_with("Sn") => {
+        Some(GsnNodeType::Solution) => {
             new_solution(id, &gsn_node.text, gsn_node.url.to_owned(), classes)
         }
-        id if id.starts_with('S') => new_strategy(
+        Some(GsnNodeType::Strategy) => new_strategy(
             id,
             &gsn_node.text,
             gsn_node.undeveloped.unwrap_or(false),
             gsn_node.url.to_owned(),
             classes,
         ),
-        id if id.starts_with('C') => {
+        Some(GsnNodeType::Context) => {
             new_context(id, &gsn_node.text, gsn_node.url.to_owned(), classes)
         }
-        id if id.starts_with('A') => {
+        Some(GsnNodeType::Assumption) => {
             new_assumption(id, &gsn_node.text, gsn_node.url.to_owned(), classes)
         }
-        id if id.starts_with('J') => {
+        Some(GsnNodeType::Justification) => {
             new_justification(id, &gsn_node.text, gsn_node.url.to_owned(), classes)
         }
-        _ => unreachable!(),
+        Some(GsnNodeType::CounterGoal) => new_counter_goal(
+            id,
+            &gsn_node.text,
+            gsn_node.undeveloped.unwrap_or(false),
+            gsn_node.url.to_owned(),
+            classes,
+        ),
+        Some(GsnNodeType::CounterSolution) => {
+            new_counter_solution(id, &gsn_node.text, gsn_node.url.to_owned(), classes)
+        }
+        Some(GsnNodeType::Module) => new_module(
+            id,
+            &gsn_node.text,
+            gsn_node
+                .url
+                .to_owned()
+                .or_else(|| get_module_reference_url(gsn_node, modules)),
+            classes,
+        ),
+        None => unreachable!(),
     }
 }
 
+///
+/// Get the CSS classes for the additional attributes of an element i.e., `gsn_<attribute>`.
+/// Attributes with value `false` or without a value are skipped.
+///
+fn get_layer_classes(gsn_node: &GsnNode) -> Option<Vec<String>> {
+    gsn_node
+        .additional
+        .iter()
+        .filter(|(_, v)| !matches!(v, serde_yaml::Value::Null | serde_yaml::Value::Bool(false)))
+        .map(|(k, _)| {
+            let mut t = escape_text(&k.to_ascii_lowercase());
+            t.insert_str(0, "gsn_");
+            Some(t.to_owned())
+        })
+        .collect()
+}
+
+///
+/// Get the link from a module reference to the argument view of the referenced module.
+///
+fn get_module_reference_url(
+    gsn_node: &GsnNode,
+    modules: &HashMap<String, Module>,
+) -> Option<String> {
+    let target = modules.get(gsn_node.module_ref.as_ref()?)?;
+    let source = modules.get(&gsn_node.module)?;
+    Some(get_relative_module_url(&target.filename, &source.filename))
+}
+
 ///
 ///
 ///
@@ -79,15 +127,7 @@ pub fn away_svg_from_gsn_node(
     module: &Module,
     source_module: &Module,
 ) -> Rc<RefCell<dyn crate::dirgraphsvg::nodes::Node>> {
-    let layer_classes: Option<Vec<String>> = gsn_node
-        .additional
-        .keys()
-        .map(|k| {
-            let mut t = escape_text(&k.to_ascii_lowercase());
-            t.insert_str(0, "gsn_");
-            Some(t.to_owned())
-        })
-        .collect();
+    let layer_classes = get_layer_classes(gsn_node);
 
     let mut mod_class = gsn_node.module.to_owned();
     mod_class.insert_str(0, "gsn_module_");
@@ -104,8 +144,8 @@ pub fn away_svg_from_gsn_node(
     let mut module_url = get_relative_module_url(&module.filename, &source_module.filename);
     module_url.push('#');
     module_url.push_str(&escape_node_id(id));
-    match id {
-        id if id.starts_with('G') => new_away_goal(
+    match gsn_node.get_type(id) {
+        Some(GsnNodeType::Goal) | Some(GsnNodeType::CounterGoal) => new_away_goal(
             id,
             &gsn_node.text,
             &gsn_node.module,
@@ -113,7 +153,7 @@ pub fn away_svg_from_gsn_node(
             gsn_node.url.to_owned(),
             classes,
         ),
-        id if id.starts_with("Sn") => new_away_solution(
+        Some(GsnNodeType::Solution) | Some(GsnNodeType::CounterSolution) => new_away_solution(
             id,
             &gsn_node.text,
             &gsn_node.module,
@@ -121,14 +161,15 @@ pub fn away_svg_from_gsn_node(
             gsn_node.url.to_owned(),
             classes,
         ),
-        id if id.starts_with('S') => new_strategy(
+        Some(GsnNodeType::Strategy) => new_away_strategy(
             id,
             &gsn_node.text,
-            gsn_node.undeveloped.unwrap_or(false),
+            &gsn_node.module,
+            Some(module_url),
             gsn_node.url.to_owned(),
             classes,
         ),
-        id if id.starts_with('C') => new_away_context(
+        Some(GsnNodeType::Context) => new_away_context(
             id,
             &gsn_node.text,
             &gsn_node.module,
@@ -136,7 +177,7 @@ pub fn away_svg_from_gsn_node(
             gsn_node.url.to_owned(),
             classes,
         ),
-        id if id.starts_with('A') => new_away_assumption(
+        Some(GsnNodeType::Assumption) => new_away_assumption(
             id,
             &gsn_node.text,
             &gsn_node.module,
@@ -144,7 +185,7 @@ pub fn away_svg_from_gsn_node(
             gsn_node.url.to_owned(),
             classes,
         ),
-        id if id.starts_with('J') => new_away_justification(
+        Some(GsnNodeType::Justification) => new_away_justification(
             id,
             &gsn_node.text,
             &gsn_node.module,
@@ -152,8 +193,166 @@ pub fn away_svg_from_gsn_node(
             gsn_node.url.to_owned(),
             classes,
         ),
-        _ => unreachable!(),
+        Some(GsnNodeType::Module) => new_module(id, &gsn_node.text, Some(module_url), classes),
+        None => unreachable!(),
+    }
+}
+
+///
+/// Collect the markers for all elements that are rendered as regular (i.e., not away) elements.
+/// If a module is given, only elements of this module are considered.
+///
+fn get_node_markers(
+    nodes: &MyMap<String, GsnNode>,
+    module: Option<&str>,
+    modules: &HashMap<String, Module>,
+) -> BTreeMap<String, Vec<NodeMarker>> {
+    // Scores are only shown if the argument makes use of the dialectic extension.
+    let scores = if has_defeaters(nodes) {
+        get_baconian_scores(nodes)
+    } else {
+        BTreeMap::new()
+    };
+    let confidences = if has_confidences(nodes) {
+        get_confidences(nodes)
+    } else {
+        BTreeMap::new()
+    };
+    nodes
+        .iter()
+        .filter(|(_, node)| module.map(|m| node.module == m).unwrap_or(true))
+        .map(|(id, node)| {
+            let mut markers = Vec::new();
+            if node.public == Some(true) {
+                markers.push(NodeMarker::Public);
+            }
+            if node.uninstantiated == Some(true) {
+                markers.push(if node.undeveloped == Some(true) {
+                    NodeMarker::UndevelopedUninstantiated
+                } else {
+                    NodeMarker::Uninstantiated
+                });
+            }
+            for (acp, _) in node
+                .acp
+                .iter()
+                .flat_map(|a| a.iter())
+                .filter(|(_, target)| target.is_none())
+            {
+                markers.push(NodeMarker::Acp(
+                    acp.to_owned(),
+                    get_acp_url(acp, node, modules),
+                ));
+            }
+            if let Some(score) = scores.get(id) {
+                markers.push(NodeMarker::Score(score.to_string()));
+            }
+            if let Some(confidence) = confidences.get(id).filter(|_| {
+                matches!(
+                    node.get_type(id),
+                    Some(GsnNodeType::Goal)
+                        | Some(GsnNodeType::Strategy)
+                        | Some(GsnNodeType::Solution)
+                )
+            }) {
+                markers.push(NodeMarker::Confidence(confidence.to_string()));
+            }
+            (id.to_owned(), markers)
+        })
+        .filter(|(_, markers)| !markers.is_empty())
+        .collect()
+}
+
+///
+/// Get the decorators of edges from multiplicities and choices of argument patterns.
+///
+fn get_edge_decorators(
+    nodes: &MyMap<String, GsnNode>,
+    modules: &HashMap<String, Module>,
+) -> BTreeMap<(String, String), Vec<EdgeDecorator>> {
+    let mut decorators = BTreeMap::<(String, String), Vec<EdgeDecorator>>::new();
+    for (id, node) in nodes.iter() {
+        for (target, multiplicity) in node.multiplicity.iter().flat_map(|m| m.iter()) {
+            let decorator = match Multiplicity::from_str(multiplicity) {
+                Ok(Multiplicity::Optional) => EdgeDecorator::Optional,
+                Ok(Multiplicity::Multiple(label)) => EdgeDecorator::Multiple(label),
+                Err(_) => continue,
+            };
+            decorators
+                .entry((id.to_owned(), target.to_owned()))
+                .or_default()
+                .push(decorator);
+        }
+        if let Some(choice) = &node.choice {
+            for target in &choice.elements {
+                decorators
+                    .entry((id.to_owned(), target.to_owned()))
+                    .or_default()
+                    .push(EdgeDecorator::Choice(choice.label.to_owned()));
+            }
+        }
+        for (acp, target) in node.acp.iter().flat_map(|a| a.iter()) {
+            if let Some(target) = target {
+                decorators
+                    .entry((id.to_owned(), target.to_owned()))
+                    .or_default()
+                    .push(EdgeDecorator::Acp(
+                        acp.to_owned(),
+                        get_acp_url(acp, node, modules),
+                    ));
+            }
+        }
+        if let Some((challenged, Some(target))) = node.get_challenged() {
+            decorators
+                .entry((challenged.to_owned(), id.to_owned()))
+                .or_default()
+                .push(EdgeDecorator::ChallengedLink(target.to_owned()));
+        }
     }
+    decorators
+}
+
+///
+/// Add the edges of the dialectic extension.
+///
+/// The edges are added from the challenged element to the challenger,
+/// such that the challenger is laid out below the challenged element.
+/// If a module is given, only edges from or to elements of this module are added.
+///
+fn add_challenge_edges(
+    nodes: &MyMap<String, GsnNode>,
+    module: Option<&str>,
+    edges: &mut BTreeMap<String, Vec<(String, EdgeType)>>,
+) {
+    for (id, node) in nodes.iter() {
+        if let Some((challenged, _)) = node.get_challenged() {
+            if let Some(challenged_node) = nodes.get(challenged) {
+                if module
+                    .map(|m| node.module == m || challenged_node.module == m)
+                    .unwrap_or(true)
+                {
+                    edges
+                        .entry(challenged.to_owned())
+                        .or_default()
+                        .push((id.to_owned(), EdgeType::OneWay(SingleEdge::Challenges)));
+                }
+            }
+        }
+    }
+}
+
+///
+/// Get the link from an Assurance Claim Point to the argument view of its confidence argument.
+///
+fn get_acp_url(acp: &str, node: &GsnNode, modules: &HashMap<String, Module>) -> Option<String> {
+    let confidence = modules
+        .values()
+        .find(|m| m.meta.as_ref().and_then(|m| m.confidence_for.as_deref()) == Some(acp))?;
+    let source = modules.get(&node.module)?;
+    Some(get_relative_module_url(
+        &confidence.filename,
+        &source.filename,
+    ))
 }
 
 ///
@@ -225,6 +424,102 @@ pub fn render_architecture(
     Ok(())
 }
 
+///
+/// Get the identifiers under which elements are rendered.
+/// Elements are shown with their identifier within the module,
+/// unless an element with the same identifier exists in another module.
+///
+fn get_render_ids(nodes: &MyMap<String, GsnNode>) -> BTreeMap<&str, &str> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for id in nodes.keys() {
+        *counts.entry(get_local_id(id)).or_default() += 1;
+    }
+    nodes
+        .keys()
+        .map(|id| {
+            let local = get_local_id(id);
+            if counts[local] == 1 {
+                (id.as_str(), local)
+            } else {
+                (id.as_str(), id.as_str())
+            }
+        })
+        .collect()
+}
+
+///
+/// Add the elements to the diagram under the identifiers from `get_render_ids`.
+/// The layout depends on the order of the identifiers,
+/// thus, renaming is done before the diagram is built.
+///
+fn add_elements<'a>(
+    dg: crate::dirgraphsvg::DirGraph<'a>,
+    render_ids: &BTreeMap<&'a str, &'a str>,
+    svg_nodes: BTreeMap<String, Rc<RefCell<dyn Node>>>,
+    edges: BTreeMap<String, Vec<(String, EdgeType)>>,
+    markers: BTreeMap<String, Vec<NodeMarker>>,
+    decorators: BTreeMap<(String, String), Vec<EdgeDecorator>>,
+    levels: BTreeMap<&'a str, Vec<&'a str>>,
+) -> crate::dirgraphsvg::DirGraph<'a> {
+    let rename = |id: &str| {
+        render_ids
+            .get(id)
+            .map_or_else(|| id.to_owned(), |r| r.to_string())
+    };
+    let mut edges = edges
+        .into_iter()
+        .map(|(id, targets)| {
+            (
+                rename(&id),
+                targets
+                    .into_iter()
+                    .map(|(target, edge_type)| (rename(&target), edge_type))
+                    .collect(),
+            )
+        })
+        .collect();
+    let mut markers = markers
+        .into_iter()
+        .map(|(id, m)| (rename(&id), m))
+        .collect();
+    let mut decorators = decorators
+        .into_iter()
+        .map(|((source, target), d)| {
+            let d = d
+                .into_iter()
+                .map(|d| match d {
+                    EdgeDecorator::ChallengedLink(link) => {
+                        EdgeDecorator::ChallengedLink(rename(&link))
+                    }
+                    d => d,
+                })
+                .collect();
+            ((rename(&source), rename(&target)), d)
+        })
+        .collect();
+    let levels = levels
+        .into_iter()
+        .map(|(level, ids)| {
+            (
+                level,
+                ids.into_iter()
+                    .map(|id| render_ids.get(id).copied().unwrap_or(id))
+                    .collect(),
+            )
+        })
+        .collect();
+    dg.add_nodes(
+        svg_nodes
+            .into_iter()
+            .map(|(id, n)| (rename(&id), n))
+            .collect(),
+    )
+    .add_edges(&mut edges)
+    .add_node_markers(&mut markers)
+    .add_edge_decorators(&mut decorators)
+    .add_levels(&levels)
+}
+
 ///
 /// Render all nodes in one diagram
 ///
@@ -233,6 +528,7 @@ pub fn render_architecture(
 pub fn render_complete(
     output: &mut impl Write,
     _matches: &clap::ArgMatches,
+    modules: &HashMap<String, Module>,
     nodes: &MyMap<String, GsnNode>,
     stylesheets: Option<Vec<&str>>,
 ) -> Result<(), anyhow::Error> {
@@ -247,14 +543,26 @@ pub fn render_complete(
         // TODO continue masking here
         .map(|(id, node)| (id.to_owned(), node.get_edges()))
         .collect();
+    add_challenge_edges(nodes, None, &mut edges);
+    let render_ids = get_render_ids(nodes);
     let svg_nodes: BTreeMap<String, Rc<RefCell<dyn Node>>> = nodes
         .iter()
-        .map(|(id, node)| (id.to_owned(), svg_from_gsn_node(id, node)))
+        .map(|(id, node)| {
+            (
+                id.to_owned(),
+                svg_from_gsn_node(render_ids[id.as_str()], node, modules),
+            )
+        })
         .collect();
-    dg = dg
-        .add_nodes(svg_nodes)
-        .add_edges(&mut edges)
-        .add_levels(&get_levels(nodes));
+    dg = add_elements(
+        dg,
+        &render_ids,
+        svg_nodes,
+        edges,
+        get_node_markers(nodes, None, modules),
+        get_edge_decorators(nodes, modules),
+        get_levels(nodes),
+    );
 
     if let Some(mut css) = stylesheets {
         dg = dg.add_css_sytlesheets(&mut css);
@@ -282,10 +590,16 @@ pub fn render_argument(
     stylesheets: Option<Vec<&str>>,
 ) -> Result<(), anyhow::Error> {
     let mut dg = crate::dirgraphsvg::DirGraph::default();
+    let render_ids = get_render_ids(nodes);
     let mut svg_nodes: BTreeMap<String, Rc<RefCell<dyn Node>>> = nodes
         .iter()
         .filter(|(_, node)| node.module == module_name)
-        .map(|(id, node)| (id.to_owned(), svg_from_gsn_node(id, node)))
+        .map(|(id, node)| {
+            (
+                id.to_owned(),
+                svg_from_gsn_node(render_ids[id.as_str()], node, modules),
+            )
+        })
         .collect();
 
     svg_nodes.append(
@@ -296,7 +610,7 @@ pub fn render_argument(
                 (
                     id.to_owned(),
                     away_svg_from_gsn_node(
-                        id,
+                        render_ids[id.as_str()],
                         node,
                         modules.get(&node.module).unwrap(),
                         modules.get(module_name).unwrap(),
@@ -322,6 +636,7 @@ pub fn render_argument(
         })
         .filter(|(_, targets)| !targets.is_empty())
         .collect();
+    add_challenge_edges(nodes, Some(module_name), &mut edges);
 
     svg_nodes = svg_nodes
         .into_iter()
@@ -332,10 +647,15 @@ pub fn render_argument(
         })
         .collect();
 
-    dg = dg
-        .add_nodes(svg_nodes)
-        .add_edges(&mut edges)
-        .add_levels(&get_levels(nodes));
+    dg = add_elements(
+        dg,
+        &render_ids,
+        svg_nodes,
+        edges,
+        get_node_markers(nodes, Some(module_name), modules),
+        get_edge_decorators(nodes, modules),
+        get_levels(nodes),
+    );
 
     if let Some(mut css) = stylesheets {
         dg = dg.add_css_sytlesheets(&mut css);
@@ -350,8 +670,11 @@ pub fn render_argument(
                 meta_info.insert(1, meta.brief.as_deref().unwrap().to_owned());
             }
             if matches.is_present("FULL_LEGEND") {
-                let add = format!("{:?}", meta.additional);
-                meta_info.append(&mut add.lines().map(|x| x.to_owned()).collect::<Vec<String>>());
+                for (key, value) in meta.additional.iter() {
+                    let add = format_attribute(key, value);
+                    meta_info
+                        .append(&mut add.lines().map(|x| x.to_owned()).collect::<Vec<String>>());
+                }
             }
         }
         dg = dg.add_meta_information(&mut meta_info);
@@ -362,6 +685,41 @@ pub fn render_argument(
     Ok(())
 }
 
+///
+/// Format the value of an additional attribute for text output.
+///
+/// Scalars are output as they are, lists are separated by commas
+/// and maps are output as `key: value` per line with nested maps being indented.
+///
+pub(crate) fn format_value(value: &serde_yaml::Value) -> String {
+    use serde_yaml::Value;
+    match value {
+        Value::Null => String::new(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => s.to_owned(),
+        Value::Sequence(seq) => seq.iter().map(format_value).collect::<Vec<_>>().join(", "),
+        Value::Mapping(map) => map
+            .iter()
+            .map(|(k, v)| format_attribute(&format_value(k), v))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+///
+/// Format an additional attribute as `key: value`.
+/// Maps start on a new line and are indented.
+///
+pub(crate) fn format_attribute(key: &str, value: &serde_yaml::Value) -> String {
+    match value {
+        serde_yaml::Value::Mapping(_) => {
+            format!("{}:\n  {}", key, format_value(value).replace('\n', "\n  "))
+        }
+        _ => format!("{}: {}", key, format_value(value)),
+    }
+}
+
 pub(crate) fn render_evidences(
     output: &mut impl Write,
     nodes: &MyMap<String, GsnNode>,
@@ -373,13 +731,14 @@ pub(crate) fn render_evidences(
 
     let solutions: Vec<(&String, &GsnNode)> = nodes
         .iter()
-        .filter(|(id, _)| id.starts_with("Sn"))
+        .filter(|(id, node)| node.get_type(id) == Some(GsnNodeType::Solution))
         .collect();
     if solutions.is_empty() {
         writeln!(output, "No evidences found.")?;
     }
     let width = (solutions.len() as f32).log10().ceil() as usize;
     for (i, (id, node)) in solutions.into_iter().enumerate() {
+        let id = get_display_id(id, &node.module);
         writeln!(
             output,
             "{:>width$}. {}: {}",
@@ -406,7 +765,7 @@ pub(crate) fn render_evidences(
                 "{: >width$}{}: {}",
                 ' ',
                 layer.to_ascii_uppercase(),
-                text.replace(
+                format_value(text).replace(
                     '\n',
                     &format!("\n{: >w$}", ' ', w = width + 2 + layer.len())
                 )
@@ -415,5 +774,177 @@ pub(crate) fn render_evidences(
         }
     }
 
+    let defeaters = get_unresolved_defeaters(nodes);
+    if !defeaters.is_empty() {
+        writeln!(output)?;
+        writeln!(output, "List of Unresolved Defeaters")?;
+        writeln!(output)?;
+        let width = (defeaters.len() as f32).log10().ceil() as usize;
+        for (i, id) in defeaters.into_iter().enumerate() {
+            let node = nodes.get(id).unwrap();
+            let id = get_display_id(id, &node.module);
+            writeln!(
+                output,
+                "{:>width$}. {}: {}",
+                i + 1,
+                id,
+                node.text
+                    .replace('\n', &format!("\n{: >w$}", ' ', w = width + 4 + id.len()))
+            )?;
+            let width = width + 2;
+            writeln!(output)?;
+            writeln!(output, "{: >width$}{}", ' ', node.module)?;
+            writeln!(output)?;
+            if let Some((challenged, link_target)) = node.get_challenged() {
+                let challenged = match link_target {
+                    Some(target) => format!(
+                        "{} {} {}",
+                        get_display_id(challenged, &node.module),
+                        CHALLENGED_LINK_SEPARATOR,
+                        get_display_id(target, &node.module)
+                    ),
+                    None => get_display_id(challenged, &node.module).to_owned(),
+                };
+                writeln!(output, "{: >width$}Challenges: {}", ' ', challenged)?;
+                writeln!(output)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+///
+/// Render the confidence of all goals.
+///
+/// This is the Baconian confidence i.e., the number of eliminated defeaters out of all identified defeaters
+/// and the propagated confidence if solutions have a confidence.
+///
+pub(crate) fn render_confidence_report(
+    output: &mut impl Write,
+    nodes: &MyMap<String, GsnNode>,
+) -> Result<(), anyhow::Error> {
+    writeln!(output)?;
+    writeln!(output, "List of Confidence Scores")?;
+    writeln!(output)?;
+
+    let scores = get_baconian_scores(nodes);
+    // Baconian confidence is only sensible if the argument makes use of the dialectic extension.
+    let with_scores = has_defeaters(nodes);
+    let confidences = if has_confidences(nodes) {
+        get_confidences(nodes)
+    } else {
+        BTreeMap::new()
+    };
+    if scores.is_empty() {
+        writeln!(output, "No goals found.")?;
+    }
+    let module = get_single_module(nodes);
+    let join = |ids: &BTreeSet<String>| {
+        ids.iter()
+            .map(|id| get_display_id(id, module))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let width = (scores.len() as f32).log10().ceil() as usize;
+    for (i, (id, score)) in scores.iter().enumerate() {
+        let name = get_display_id(id, module);
+        if with_scores {
+            writeln!(output, "{:>width$}. {}: {}", i + 1, name, score)?;
+        } else {
+            writeln!(output, "{:>width$}. {}", i + 1, name)?;
+        }
+        let width = width + 2;
+        writeln!(output)?;
+        if let Some(confidence) = confidences.get(id) {
+            writeln!(output, "{: >width$}Confidence: {}", ' ', confidence)?;
+            writeln!(output)?;
+        }
+        if !score.eliminated.is_empty() {
+            writeln!(
+                output,
+                "{: >width$}Eliminated: {}",
+                ' ',
+                join(&score.eliminated)
+            )?;
+            writeln!(output)?;
+        }
+        if !score.residual.is_empty() {
+            writeln!(
+                output,
+                "{: >width$}Residual: {}",
+                ' ',
+                join(&score.residual)
+            )?;
+            writeln!(output)?;
+        }
+    }
+
+    Ok(())
+}
+
+///
+/// Output the metrics of the complete argument and of each module as Markdown tables.
+///
+pub(crate) fn render_metrics(
+    output: &mut impl Write,
+    nodes: &MyMap<String, GsnNode>,
+    modules: &HashMap<String, Module>,
+) -> Result<(), anyhow::Error> {
+    let mut module_names: Vec<&String> = modules.keys().collect();
+    module_names.sort();
+    let overall = get_metrics(nodes, None);
+    let per_module: Vec<Metrics> = module_names
+        .iter()
+        .map(|m| get_metrics(nodes, Some(m)))
+        .collect();
+    let format_metric = |value: Option<f64>| match value {
+        Some(v) if v.fract() == 0.0 => format!("{}", v),
+        Some(v) => format!("{:.1}", v),
+        None => "-".to_owned(),
+    };
+
+    writeln!(output)?;
+    writeln!(output, "Metrics")?;
+    writeln!(output)?;
+    writeln!(
+        output,
+        "| Metric | Overall | {} |",
+        module_names
+            .iter()
+            .map(|m| m.as_str())
+            .collect::<Vec<_>>()
+            .join(" | ")
+    )?;
+    writeln!(output, "|---|---:|{}", "---:|".repeat(module_names.len()))?;
+    for name in METRIC_NAMES {
+        writeln!(
+            output,
+            "| {} | {} | {} |",
+            name,
+            format_metric(overall.get(name)),
+            per_module
+                .iter()
+                .map(|m| format_metric(m.get(name)))
+                .collect::<Vec<_>>()
+                .join(" | ")
+        )?;
+    }
+    writeln!(output)?;
+    writeln!(output, "Evidence per Top-Level Subgoal")?;
+    writeln!(output)?;
+    if overall.evidence_per_subgoal.is_empty() {
+        writeln!(output, "No top-level subgoals found.")?;
+    }
+    let module = get_single_module(nodes);
+    for (subgoal, solutions) in &overall.evidence_per_subgoal {
+        writeln!(
+            output,
+            "- {}: {}",
+            get_display_id(subgoal, module),
+            solutions
+        )?;
+    }
+
     Ok(())
 }
@@ -1,13 +1,18 @@
+use crate::diagnostics::SourceLocation;
+use crate::gsn::{IN_CONTEXT_OF, SUPPORTED_BY};
 use serde::de::{Deserialize, Deserializer, MapAccess, Visitor};
+use serde::{Serialize, Serializer};
 use std::collections::BTreeMap;
 use std::fmt::Debug;
 use std::marker::PhantomData;
+use yaml_rust::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust::scanner::Marker;
 
 // Copied and adapted from https://serde.rs/deserialize-map.html
 // to work around an issue in serde_yaml that does not check for duplicate keys in input YAML.
 // Duplicate keys are no valid YAML but this is ignored by serde_yaml.
 
-#[derive(Default, PartialEq)]
+#[derive(Clone, Default, PartialEq)]
 pub struct MyMap<K, V>(BTreeMap<K, V>)
 where
     K: Ord;
@@ -107,6 +112,155 @@ where
     }
 }
 
+// Serializing is not affected by the issue, thus it is forwarded to the inner map.
+impl<K, V> Serialize for MyMap<K, V>
+where
+    K: Serialize + Ord,
+    V: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+///
+/// Locations of the elements and their references in an input file.
+///
+/// serde_yaml does not provide locations of the deserialized values,
+/// thus, the input is scanned a second time with yaml-rust.
+///
+#[derive(Debug, Default)]
+pub struct YamlLocations {
+    /// Location of the identifier of each element
+    pub nodes: BTreeMap<String, SourceLocation>,
+    /// Location of each entry in `supportedBy` or `inContextOf` per element,
+    /// keyed by attribute and index, since references can be listed more than once
+    pub references: BTreeMap<String, BTreeMap<(String, usize), SourceLocation>>,
+}
+
+///
+/// Attributes whose list items are references to other elements
+///
+const REFERENCE_ATTRIBUTES: [&str; 2] = [SUPPORTED_BY, IN_CONTEXT_OF];
+
+enum Container {
+    Mapping {
+        expect_key: bool,
+        key: Option<String>,
+    },
+    Sequence {
+        index: usize,
+    },
+}
+
+struct LocationReceiver<'a> {
+    file: &'a str,
+    stack: Vec<Container>,
+    locations: YamlLocations,
+}
+
+impl<'a> LocationReceiver<'a> {
+    fn location(&self, mark: &Marker, value: &str) -> SourceLocation {
+        SourceLocation {
+            file: self.file.to_owned(),
+            line: mark.line(),
+            col: mark.col() + 1,
+            len: value.chars().count(),
+        }
+    }
+
+    ///
+    /// Get the key of the mapping at the given depth.
+    ///
+    fn key(&self, depth: usize) -> Option<&String> {
+        match self.stack.get(depth) {
+            Some(Container::Mapping { key, .. }) => key.as_ref(),
+            _ => None,
+        }
+    }
+
+    ///
+    /// A value has been completed in the current container.
+    ///
+    fn value_done(&mut self) {
+        match self.stack.last_mut() {
+            Some(Container::Mapping { expect_key, .. }) => *expect_key = true,
+            Some(Container::Sequence { index }) => *index += 1,
+            None => (),
+        }
+    }
+}
+
+impl<'a> MarkedEventReceiver for LocationReceiver<'a> {
+    fn on_event(&mut self, ev: Event, mark: Marker) {
+        match ev {
+            Event::MappingStart(_) => self.stack.push(Container::Mapping {
+                expect_key: true,
+                key: None,
+            }),
+            Event::SequenceStart(_) => self.stack.push(Container::Sequence { index: 0 }),
+            Event::MappingEnd | Event::SequenceEnd => {
+                self.stack.pop();
+                self.value_done();
+            }
+            Event::Scalar(value, ..) => {
+                let depth = self.stack.len();
+                // Items of a reference list of an element
+                let reference_of = match (depth, self.key(0), self.key(1)) {
+                    (3, Some(node), Some(attribute))
+                        if REFERENCE_ATTRIBUTES.contains(&attribute.as_str()) =>
+                    {
+                        Some((node.to_owned(), attribute.to_owned()))
+                    }
+                    _ => None,
+                };
+                let location = self.location(&mark, &value);
+                match self.stack.last_mut() {
+                    Some(Container::Mapping { expect_key, key }) if *expect_key => {
+                        *expect_key = false;
+                        *key = Some(value.to_owned());
+                        if depth == 1 {
+                            self.locations.nodes.insert(value, location);
+                        }
+                    }
+                    Some(Container::Mapping { .. }) => self.value_done(),
+                    Some(Container::Sequence { index }) => {
+                        if let Some((node, attribute)) = reference_of {
+                            self.locations
+                                .references
+                                .entry(node)
+                                .or_default()
+                                .insert((attribute, *index), location);
+                        }
+                        self.value_done();
+                    }
+                    None => (),
+                }
+            }
+            Event::Alias(_) => self.value_done(),
+            _ => (),
+        }
+    }
+}
+
+///
+/// Get the locations of all elements and their references in the input.
+/// Errors are ignored, since they are already reported when deserializing the input.
+///
+pub fn get_locations(file: &str, input: &str) -> YamlLocations {
+    let mut receiver = LocationReceiver {
+        file,
+        stack: Vec::new(),
+        locations: YamlLocations::default(),
+    };
+    let mut parser = Parser::new(input.chars());
+    let _ = parser.load(&mut receiver, false);
+    receiver.locations
+}
+
 #[cfg(test)]
 mod test {
 
@@ -140,4 +294,49 @@ mod test {
             "Err(Message(\"invalid type: sequence, expected a map with unique keys\", Some(Pos { marker: Marker { index: 0, line: 1, col: 0 }, path: \".\" })))"
         );
     }
+    #[test]
+    fn locations() {
+        let input = "G1:\n  text: Goal\n  supportedBy: [S1, Sn1]\n  inContextOf:\n    - C1\n\nS1:\n  text: 'supportedBy: [X]'\n";
+        let locations = get_locations("test.yaml", input);
+        assert_eq!(locations.nodes.len(), 2);
+        assert_eq!(
+            locations.nodes.get("G1").unwrap().to_string(),
+            "test.yaml:1:1"
+        );
+        assert_eq!(
+            locations.nodes.get("S1").unwrap().to_string(),
+            "test.yaml:7:1"
+        );
+        let refs = locations.references.get("G1").unwrap();
+        let location = |attribute: &str, index: usize| {
+            refs.get(&(attribute.to_owned(), index))
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(refs.len(), 3);
+        assert_eq!(location("supportedBy", 0), "test.yaml:3:17");
+        assert_eq!(location("supportedBy", 1), "test.yaml:3:21");
+        assert_eq!(location("inContextOf", 0), "test.yaml:5:7");
+        assert!(!locations.references.contains_key("S1"));
+    }
+
+    #[test]
+    fn duplicate_reference_locations() {
+        let input = "G1:\n  supportedBy: [S1, S1]\n  inContextOf: [C1]\n";
+        let locations = get_locations("test.yaml", input);
+        let refs = locations.references.get("G1").unwrap();
+        assert_eq!(refs.len(), 3);
+        assert_eq!(
+            refs.get(&("supportedBy".to_owned(), 1))
+                .unwrap()
+                .to_string(),
+            "test.yaml:2:21"
+        );
+        assert_eq!(
+            refs.get(&("inContextOf".to_owned(), 0))
+                .unwrap()
+                .to_string(),
+            "test.yaml:3:17"
+        );
+    }
 }
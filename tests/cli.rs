@@ -128,7 +128,7 @@ mod integrations {
             .arg("examples/modular/main.gsn.yaml")
             .arg("examples/modular/sub2.gsn.yaml");
         cmd.assert().failure().stderr(predicate::str::contains(
-            "Error: 1 errors and 0 warnings detected.",
+            "Error: 2 errors and 1 warnings detected.",
         ));
         Ok(())
     }
@@ -142,11 +142,605 @@ mod integrations {
             .arg("-x")
             .arg("examples/modular/sub2.gsn.yaml");
         cmd.assert().failure().stderr(predicate::str::contains(
-            "Error: 1 errors and 0 warnings detected.",
+            "Error: 2 errors and 1 warnings detected.",
         ));
         Ok(())
     }
 
+    #[test]
+    fn validate_qualified_references() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        cmd.arg("-c")
+            .arg("tests/qualified_main.gsn.test.yaml")
+            .arg("tests/qualified_sub.gsn.test.yaml");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::is_empty())
+            .stderr(predicate::str::is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn validate_private_reference() -> Result<(), Box<dyn std::error::Error>> {
+        let temp = assert_fs::TempDir::new()?;
+        temp.copy_from("tests", &["qualified_*.gsn.test.yaml"])?;
+        let sub = temp.child("qualified_sub.gsn.test.yaml");
+        let content = std::fs::read_to_string(sub.path())?
+            .replace("  text: Solution\n", "  text: Solution\n  public: true\n");
+        sub.write_str(&content)?;
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        cmd.current_dir(&temp)
+            .arg("-c")
+            .arg("qualified_main.gsn.test.yaml")
+            .arg("qualified_sub.gsn.test.yaml");
+        cmd.assert().failure().stderr(predicate::str::contains(
+            "C09: Element S1 references private element Sub::G1 of module Sub.",
+        ));
+        temp.close()?;
+        Ok(())
+    }
+
+    #[test]
+    fn validate_pattern() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        cmd.arg("-c").arg("tests/pattern.gsn.test.yaml");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::is_empty())
+            .stderr(predicate::str::is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn instantiate_pattern() -> Result<(), Box<dyn std::error::Error>> {
+        let temp = assert_fs::TempDir::new()?;
+        let instance = temp.child("hazards.gsn.yaml");
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        cmd.arg("-i")
+            .arg("tests/pattern_data.test.yaml")
+            .arg("-o")
+            .arg(instance.path())
+            .arg("tests/pattern.gsn.test.yaml");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::is_empty())
+            .stderr(predicate::str::is_empty());
+        instance.assert(predicate::str::contains("name: hazards"));
+        instance.assert(predicate::str::contains(
+            "G2_2:\n  text: Hazard Flood is mitigated",
+        ));
+        instance.assert(predicate::str::contains(
+            "G4:\n  text: Hazards are mitigated by emergency shutdown",
+        ));
+        instance.assert(predicate::str::contains("G3:").not());
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        cmd.arg("-c").arg(instance.path());
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::is_empty())
+            .stderr(predicate::str::is_empty());
+        temp.close()?;
+        Ok(())
+    }
+
+    #[test]
+    fn instantiate_pattern_without_choice() -> Result<(), Box<dyn std::error::Error>> {
+        let temp = assert_fs::TempDir::new()?;
+        let instance = temp.child("hazards.gsn.yaml");
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        cmd.arg("-i")
+            .arg("tests/pattern_data.test.csv")
+            .arg("-o")
+            .arg(instance.path())
+            .arg("tests/pattern.gsn.test.yaml");
+        cmd.assert().failure().stderr(predicate::str::contains(
+            "I04: Choice of element S1 requires 1 of 2 alternatives, but the data selects 0.",
+        ));
+        instance.assert(predicate::path::missing());
+        temp.close()?;
+        Ok(())
+    }
+
+    #[test]
+    fn instantiate_pattern_multiplicity_bounds() -> Result<(), Box<dyn std::error::Error>> {
+        let temp = assert_fs::TempDir::new()?;
+        temp.copy_from("tests", &["pattern.gsn.test.yaml"])?;
+        let pattern = temp.child("pattern.gsn.test.yaml");
+        let content = std::fs::read_to_string(pattern.path())?.replace("G2: n", "G2: at least 2");
+        pattern.write_str(&content)?;
+        let data = temp.child("hazards.yaml");
+        data.write_str("H: [Fire]\nProcedure: emergency shutdown\n")?;
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        cmd.arg("-i").arg(data.path()).arg(pattern.path());
+        cmd.assert().failure().stderr(predicate::str::contains(
+            "I05: Multiplicity of element S1 for G2 does not allow a list of length 1 in the data.",
+        ));
+        temp.child("hazards.gsn.yaml")
+            .assert(predicate::path::missing());
+        temp.close()?;
+        Ok(())
+    }
+
+    #[test]
+    fn assurance_claim_points() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        let temp = assert_fs::TempDir::new()?;
+        temp.copy_from("tests", &["acp_*.gsn.test.yaml"])?;
+        let input_file1 = temp.child("acp_main.gsn.test.yaml");
+        let input_file2 = temp.child("acp_confidence.gsn.test.yaml");
+        let output_file = temp.child("acp_main.gsn.test.svg");
+        cmd.arg(input_file1.as_os_str())
+            .arg(input_file2.as_os_str())
+            .arg("-A")
+            .arg("-E")
+            .arg("-F");
+        cmd.assert().success().stdout(predicate::str::is_empty());
+        output_file.assert(predicate::str::contains("class=\"gsnacp\""));
+        output_file.assert(predicate::str::contains(
+            "xlink:href=\"./acp_confidence.gsn.test.svg\"",
+        ));
+        temp.close()?;
+        Ok(())
+    }
+
+    #[test]
+    fn dialectic() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        let temp = assert_fs::TempDir::new()?;
+        temp.copy_from("tests", &["dialectic.gsn.test.yaml"])?;
+        let input_file = temp.child("dialectic.gsn.test.yaml");
+        let output_file = temp.child("dialectic.gsn.test.svg");
+        let evidence_file = temp.child("evidences.md");
+        let report_file = temp.child("confidence.md");
+        cmd.arg(input_file.as_os_str())
+            .arg("-e")
+            .arg(evidence_file.as_os_str())
+            .arg("-b")
+            .arg(report_file.as_os_str());
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("C10: Defeater CG2 is unresolved."))
+            .stderr(predicate::str::contains("CG1 is unresolved").not());
+        output_file.assert(predicate::str::contains("gsncountergoal"));
+        output_file.assert(predicate::str::contains("gsncountersltn"));
+        output_file.assert(predicate::str::contains("gsnchallenges"));
+        evidence_file.assert(predicate::str::contains("List of Unresolved Defeaters"));
+        evidence_file.assert(predicate::str::contains("1. CG2: Hazard H2"));
+        output_file.assert(predicate::str::contains("class=\"gsnscore\""));
+        report_file.assert(predicate::str::contains("1. G1: 1|2"));
+        report_file.assert(predicate::str::contains("3. G3: 0|1"));
+        report_file.assert(predicate::str::contains("Residual: CG2"));
+        temp.close()?;
+        Ok(())
+    }
+
+    #[test]
+    fn confidence_propagation() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        let temp = assert_fs::TempDir::new()?;
+        temp.copy_from("tests", &["confidence.gsn.test.yaml"])?;
+        let input_file = temp.child("confidence.gsn.test.yaml");
+        let output_file = temp.child("confidence.gsn.test.svg");
+        let report_file = temp.child("confidence.md");
+        cmd.arg(input_file.as_os_str())
+            .arg("-E")
+            .arg("-t")
+            .arg("0.8")
+            .arg("-b")
+            .arg(report_file.as_os_str());
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "C11: Confidence 0.70/0.10/0.20 of goal G3 is below the threshold 0.8.",
+            ))
+            .stderr(predicate::str::contains("goal G1").not());
+        output_file.assert(predicate::str::contains("class=\"gsnconfidence\""));
+        report_file.assert(predicate::str::contains("1. G1\n"));
+        report_file.assert(predicate::str::contains("Confidence: 0.85/0.10/0.05"));
+        report_file.assert(predicate::str::contains("Confidence: 0.90"));
+        temp.close()?;
+        Ok(())
+    }
+
+    #[test]
+    fn structured_layers() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        let temp = assert_fs::TempDir::new()?;
+        temp.copy_from("tests", &["structured_layers.gsn.test.yaml"])?;
+        let input_file = temp.child("structured_layers.gsn.test.yaml");
+        let output_file = temp.child("structured_layers.gsn.test.svg");
+        let evidence_file = temp.child("evidences.md");
+        cmd.arg(input_file.as_os_str())
+            .arg("-l")
+            .arg("requirements")
+            .arg("-l")
+            .arg("tests")
+            .arg("-l")
+            .arg("owner")
+            .arg("-g")
+            .arg("-e")
+            .arg(evidence_file.as_os_str());
+        cmd.assert().success();
+        output_file.assert(predicate::str::contains(
+            "class=\"gsnelem gsngoal gsn_module_Structured\"",
+        ));
+        output_file.assert(predicate::str::contains(
+            "gsn_owner gsn_requirements gsn_reviewed",
+        ));
+        output_file.assert(predicate::str::contains("revision: 3"));
+        output_file.assert(predicate::str::contains("v2: Added tests"));
+        evidence_file.assert(predicate::str::contains("REQUIREMENTS: REQ-1, REQ-2"));
+        evidence_file.assert(predicate::str::contains("TESTS: 42"));
+        evidence_file.assert(predicate::str::contains("team: Safety"));
+        temp.close()?;
+        Ok(())
+    }
+
+    #[test]
+    fn source_locations() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        cmd.arg("-c").arg("tests/locations.gsn.test.yaml");
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "tests/locations.gsn.test.yaml:11:1: V02: Element G2 is undeveloped.",
+            ))
+            .stderr(predicate::str::contains(
+                "tests/locations.gsn.test.yaml:3:21: C03: Element G1 has unresolved supported by element: G3",
+            ));
+        Ok(())
+    }
+
+    #[test]
+    fn rich_messages() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        cmd.arg("-c")
+            .arg("--message-format")
+            .arg("rich")
+            .arg("tests/locations.gsn.test.yaml");
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "error[C03]: Element G1 has unresolved supported by element: G3\n \
+                 --> tests/locations.gsn.test.yaml:3:21\n  \
+                 |\n\
+                 3 |   supportedBy: [S1, G3]\n  \
+                 |                     ^^\n",
+            ))
+            .stderr(predicate::str::contains("\x1b").not());
+        Ok(())
+    }
+
+    #[test]
+    fn machine_readable_messages() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        cmd.arg("-c")
+            .arg("--message-format")
+            .arg("github")
+            .arg("tests/locations.gsn.test.yaml");
+        cmd.assert()
+            .failure()
+            .stdout(predicate::str::contains(
+                "::error file=tests/locations.gsn.test.yaml,line=3,col=21,endColumn=23,title=C03::",
+            ))
+            .stderr(predicate::str::contains("C03").not());
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        cmd.arg("-c")
+            .arg("--message-format")
+            .arg("sarif")
+            .arg("tests/locations.gsn.test.yaml");
+        cmd.assert()
+            .failure()
+            .stdout(predicate::str::contains(r#""ruleId": "V02""#));
+        Ok(())
+    }
+
+    #[test]
+    fn suppressed_warnings() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        cmd.arg("-c").arg("tests/suppress.gsn.test.yaml");
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("V02: Element G3 is undeveloped."))
+            .stderr(predicate::str::contains("Element G2").not())
+            .stderr(predicate::str::contains(
+                "C05: Level top is only used once.",
+            ));
+        Ok(())
+    }
+
+    #[test]
+    fn configured_severities() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        cmd.arg("-c")
+            .arg("--config")
+            .arg("tests/severity.config.test.yaml")
+            .arg("tests/suppress.gsn.test.yaml");
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "Error: (tests_suppress_gsn_test_yaml) tests/suppress.gsn.test.yaml:10:1: V02: Element G3 is undeveloped.",
+            ))
+            .stderr(predicate::str::contains("C05").not());
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        cmd.arg("-c")
+            .arg("--config")
+            .arg("tests/severity.config.test.yaml")
+            .arg("--warn")
+            .arg("V02")
+            .arg("tests/suppress.gsn.test.yaml");
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("Warning: 1 warnings detected."));
+        Ok(())
+    }
+
+    #[test]
+    fn deny_warnings() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        cmd.arg("-c")
+            .arg("--deny-warnings")
+            .arg("tests/suppress.gsn.test.yaml");
+        cmd.assert().failure().stderr(predicate::str::contains(
+            "0 errors and 2 warnings detected.",
+        ));
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        cmd.arg("-c")
+            .arg("--deny-warnings")
+            .arg("--allow")
+            .arg("V02,C05")
+            .arg("tests/suppress.gsn.test.yaml");
+        cmd.assert().success();
+        Ok(())
+    }
+
+    #[test]
+    fn warning_baseline() -> Result<(), Box<dyn std::error::Error>> {
+        let baseline = assert_fs::NamedTempFile::new("baseline.yaml")?;
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        cmd.arg("-c")
+            .arg("--write-baseline")
+            .arg(baseline.path())
+            .arg("tests/suppress.gsn.test.yaml");
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("Warning: 2 warnings detected."));
+        baseline.assert(predicate::str::contains("element: G3"));
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        cmd.arg("-c")
+            .arg("--deny-warnings")
+            .arg("--baseline")
+            .arg(baseline.path())
+            .arg("tests/suppress.gsn.test.yaml");
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("Warning").not());
+        Ok(())
+    }
+
+    #[test]
+    fn fix_mode() -> Result<(), Box<dyn std::error::Error>> {
+        let temp = assert_fs::TempDir::new()?;
+        temp.copy_from("tests", &["fixable.gsn.test.yaml"])?;
+        let input_file = temp.child("fixable.gsn.test.yaml");
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        cmd.arg("-c").arg("--fix").arg(input_file.path());
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "V05: Removed duplicate entry S1 from supportedBy of G1.",
+            ))
+            .stderr(predicate::str::contains(
+                "V04: Moved C1 from supportedBy to inContextOf of G1.",
+            ))
+            .stderr(predicate::str::contains("V02: Marked G2 as undeveloped."))
+            .stderr(predicate::str::contains("Warning").not());
+        input_file.assert(predicate::str::contains(
+            "G1:\n  text: Goal\n  supportedBy: [S1]\n  inContextOf: [C1]\n\nS1:",
+        ));
+        input_file.assert(predicate::str::contains(
+            "G2:\n  text: Sub goal\n  undeveloped: true\n\nG3:",
+        ));
+        input_file.assert(predicate::str::contains("# G2 is developed later"));
+        Ok(())
+    }
+
+    #[test]
+    fn structural_lints() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        cmd.arg("-c").arg("--lint").arg("examples/example.gsn.yaml");
+        cmd.assert().success().stderr(predicate::str::contains(
+            "L05: Strategy S2 is not explained by a context or justification.",
+        ));
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        cmd.arg("-c")
+            .arg("--lint")
+            .arg("--allow")
+            .arg("L05,L07")
+            .arg("examples/example.gsn.yaml");
+        cmd.assert().success().stderr(predicate::str::is_empty());
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        cmd.arg("-c")
+            .arg("--lint=single-child-strategy,L02")
+            .arg("--lint-max-solutions")
+            .arg("1")
+            .arg("examples/example.gsn.yaml");
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "L02: Goal G5 is directly supported by 2 solutions, more than 1.",
+            ))
+            .stderr(predicate::str::contains("L05").not());
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        cmd.arg("-c")
+            .arg("--lint=single-child-strategies")
+            .arg("examples/example.gsn.yaml");
+        cmd.assert().failure().stderr(predicate::str::contains(
+            "Unknown lint rule single-child-strategies (did you mean single-child-strategy?)",
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn text_lints() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        cmd.arg("-c").arg("--lint").arg("examples/example.gsn.yaml");
+        cmd.assert().success().stderr(predicate::str::contains(
+            "L07: Goal G1 has no verb, it should be a proposition.",
+        ));
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        cmd.arg("-c")
+            .arg("--lint")
+            .arg("--config")
+            .arg("tests/lint.config.test.yaml")
+            .arg("examples/example.gsn.yaml");
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "L09: Text of element C2 has 3 words, more than 2.",
+            ))
+            .stderr(predicate::str::contains(
+                "L10: Text of element C2 contains placeholder Multiline.",
+            ))
+            .stderr(predicate::str::contains("L07").not());
+        Ok(())
+    }
+
+    #[test]
+    fn ordered_levels() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        cmd.arg("-c").arg("tests/levels.gsn.test.yaml");
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "V14: Element G5 has undeclared level componnt (did you mean component?).",
+            ))
+            .stderr(predicate::str::contains(
+                "tests/levels.gsn.test.yaml:22:17: C12: Element G3 on level component is supported by element G4 on level subsystem, which is above.",
+            ))
+            .stderr(predicate::str::contains("2 errors"));
+        Ok(())
+    }
+
+    #[test]
+    fn metrics_and_thresholds() -> Result<(), Box<dyn std::error::Error>> {
+        let metrics_file = assert_fs::NamedTempFile::new("metrics.md")?;
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        cmd.arg("-c")
+            .arg("--metrics")
+            .arg(metrics_file.path())
+            .arg("examples/example.gsn.yaml");
+        cmd.assert().success();
+        metrics_file.assert(predicate::str::contains(
+            "| Metric | Overall | examples_example_gsn_yaml |",
+        ));
+        metrics_file.assert(predicate::str::contains("| depth | 5 | 5 |"));
+        metrics_file.assert(predicate::str::contains("| undevelopedGoals | 20 | 20 |"));
+        metrics_file.assert(predicate::str::contains("- G7: 0"));
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        cmd.arg("-c")
+            .arg("--config")
+            .arg("tests/metrics.config.test.yaml")
+            .arg("examples/example.gsn.yaml");
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "M01: Metric undevelopedGoals is 20, above the maximum of 5.",
+            ))
+            .stderr(predicate::str::contains("1 errors"));
+        Ok(())
+    }
+
+    #[test]
+    fn evidence_files() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        cmd.arg("-c")
+            .arg("--check-evidence")
+            .arg("tests/evidence.gsn.test.yaml");
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "C13: Evidence file tests/missing_review.pdf of element Sn2 does not exist.",
+            ))
+            .stderr(predicate::str::contains(
+                "C14: Evidence file tests/evidence_report.test.txt of element Sn3 has changed.",
+            ))
+            .stderr(predicate::str::contains("Sn1").not())
+            .stderr(predicate::str::contains("Sn4").not())
+            .stderr(predicate::str::contains("2 errors"));
+        // Evidence files are only checked on request.
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        cmd.arg("-c").arg("tests/evidence.gsn.test.yaml");
+        cmd.assert().success();
+        Ok(())
+    }
+
+    #[test]
+    fn all_problems_in_one_run() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        cmd.arg("-c").arg("tests/all_problems.gsn.test.yaml");
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "V01: Elememt X1 is of unknown type.",
+            ))
+            .stderr(predicate::str::contains(
+                "C03: Element G1 has unresolved context: C9",
+            ))
+            .stderr(predicate::str::contains(
+                "C03: Element G1 has unresolved supported by element: G9",
+            ))
+            .stderr(predicate::str::contains(
+                "C04: Cycle detected at element G4. Cycle is G3 -> G4 -> G3.",
+            ))
+            .stderr(predicate::str::contains(
+                "C04: Cycle detected at element G2. Cycle is S1 -> G2 -> S1.",
+            ))
+            .stderr(predicate::str::contains(
+                "5 errors and 1 warnings detected.",
+            ));
+        Ok(())
+    }
+
+    #[test]
+    fn all_duplicates_in_one_run() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        cmd.arg("-c")
+            .arg("--message-format")
+            .arg("rich")
+            .arg("tests/duplicates.gsn.test.yaml")
+            .arg("tests/duplicates.gsn.test.yaml");
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("error[C06]"))
+            .stderr(predicate::str::contains(
+                "error[C07]: Element G1 in tests/duplicates.gsn.test.yaml was already present in tests/duplicates.gsn.test.yaml (module duplicates).",
+            ))
+            .stderr(predicate::str::contains(
+                "error[C07]: Element G2 in tests/duplicates.gsn.test.yaml was already present in tests/duplicates.gsn.test.yaml (module duplicates).",
+            ))
+            .stderr(predicate::str::contains("-- first defined here"));
+        Ok(())
+    }
+
+    #[test]
+    fn suggestions() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("gsn2x")?;
+        cmd.arg("-c").arg("tests/typos.gsn.test.yaml");
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "V01: Element Sn21 has unknown type solutoin (did you mean solution?).",
+            ))
+            .stderr(predicate::str::contains(
+                "C03: Element S1 has unresolved supported by element: G3a (did you mean G3A?)",
+            ))
+            .stderr(predicate::str::contains(
+                "C03: Element S1 has unresolved supported by element: Sn12 (did you mean Sn21?)",
+            ));
+        Ok(())
+    }
+
     #[test]
     fn no_evidences() -> Result<(), Box<dyn std::error::Error>> {
         let mut cmd = Command::cargo_bin("gsn2x")?;